@@ -0,0 +1,150 @@
+use std::{fs, path::{Path, PathBuf}, rc::Rc};
+use crate::{
+    errors::{Span, DebugInfo},
+    lexer::{
+        Lexer,
+        token::{Token, TokenTag, Lexeme}
+    },
+    parser::{Parser, ast::statement::Statement},
+    interpreter::{Interpreter, Value}
+};
+use super::Error;
+
+/// Interpreter embedded into the host program.
+///
+/// Everything the scripts define is kept between the calls,
+/// so the host can evaluate a script and call its functions later.
+/// ```ignore
+/// let mut engine = Engine::new();
+/// engine.eval("func square(x) -> x * x;")?;
+/// let value = engine.call_function("square", vec![Value::Int(4)])?;
+/// ```
+pub struct Engine {
+    interpreter: Interpreter
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self {
+            interpreter: Interpreter::new()
+        }
+    }
+
+    /// Evaluate the source, returning the value of its last statement.
+    /// Modules are imported relative to the working directory.
+    pub fn eval(&mut self, src: &str) -> Result<Value, Error> {
+        let statements = parse(Lexer::from_string(src.into()))?;
+
+        self.interpreter
+            .interpret(&statements)
+            .map_err(Error::from)
+    }
+
+    /// Evaluate the source just like `eval`, passing the value of every
+    /// expression statement to `on_value` as soon as it's executed.
+    /// ```ignore
+    /// engine.eval_each("1; print 2; 3;", |value| println!("{value:?}"))?;
+    /// ```
+    pub fn eval_each<F>(&mut self, src: &str, on_value: F) -> Result<Value, Error>
+    where
+        F: FnMut(&Value)
+    {
+        let statements = parse(Lexer::from_string(src.into()))?;
+
+        self.interpreter
+            .interpret_each(&statements, on_value)
+            .map_err(Error::from)
+    }
+
+    /// Evaluate the script file, returning the value of its last statement.
+    /// Modules are imported relative to the file.
+    pub fn eval_file(&mut self, path: &str) -> Result<Value, Error> {
+        let src = fs::read_to_string(path).map_err(|error| Error::Io {
+            path: path.into(),
+            error: error
+        })?;
+
+        let statements = parse(Lexer::new(src, path.into()))?;
+
+        self.interpreter
+            .interpret_file(&statements, Path::new(path))
+            .map_err(Error::from)
+    }
+
+    /// Call the global function, built-in ones included
+    pub fn call_function(&mut self, name: &str, args: Vec<Value>) -> Result<Value, Error> {
+        self.interpreter
+            .call_function(name, args, host_span(name))
+            .map_err(Error::from)
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.interpreter.get_global(name)
+    }
+
+    /// Define the global variable, or override the existing one
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        self.interpreter.set_global(name, value)
+    }
+
+    /// Set the directories modules are searched in after the directory
+    /// of the importing file. The `NOVIS_PATH` environment variable
+    /// is used until they are set.
+    pub fn set_search_path(&mut self, dirs: Vec<PathBuf>) {
+        self.interpreter.set_search_path(dirs)
+    }
+
+    /// Register the Rust function callable from the scripts by the `name`.
+    ///
+    /// The function is always called with `arity` arguments, otherwise
+    /// the call fails before reaching it. Error message it returns is
+    /// raised as a runtime error at the call site, which scripts may catch.
+    /// ```ignore
+    /// engine.register_fn("greet", 1, |args| match args[0].as_str() {
+    ///     Some(name) => Ok(Value::string(format!("Hello, {name}!"))),
+    ///     None => Err("expected string".into())
+    /// });
+    /// ```
+    pub fn register_fn<F>(&mut self, name: &str, arity: usize, func: F)
+    where
+        F: Fn(Vec<Value>) -> Result<Value, String> + 'static
+    {
+        self.interpreter.register_fn(name, arity, func)
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse(mut lexer: Lexer) -> Result<Vec<Statement>, Error> {
+    let tokens = lexer.lex().map_err(Error::Lexical)?;
+
+    Parser::new(tokens).parse().map_err(Error::Parse)
+}
+
+/// Span of the call made by the host, errors of the call itself
+/// point to the function name as if it was the source
+fn host_span(name: &str) -> Span {
+    let len = name
+        .split('\n')
+        .next()
+        .map_or(0, |line| line.chars().count());
+
+    Span::from(Token {
+        tag: TokenTag::Identifier(name.into()),
+        lexeme: Lexeme {
+            start: 0,
+            end: name.len()
+        },
+        info: DebugInfo {
+            fname: "<host>".into(),
+            line: 1,
+            col: 1,
+            len: len,
+            src: Rc::new(name.into())
+        }
+    })
+}
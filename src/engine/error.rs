@@ -0,0 +1,65 @@
+use std::{
+    fmt::{self, Display},
+    io
+};
+use crate::{
+    errors::DescribableError,
+    lexer::LexicalError,
+    parser::ParseError,
+    interpreter::RuntimeError
+};
+
+/// Error of the script evaluation
+#[derive(Debug)]
+pub enum Error {
+    /// Script file can't be read
+    Io {
+        path: String,
+        error: io::Error
+    },
+    Lexical(Vec<LexicalError>),
+    Parse(Vec<ParseError>),
+    /// Boxed, as it's much larger than the other errors
+    Runtime(Box<RuntimeError>)
+}
+
+impl Error {
+    /// Print the error along with the source snippets to the standard error
+    pub fn print(&self) {
+        match self {
+            Self::Io { .. } => eprintln!("Fatal error: {self}"),
+            Self::Lexical(errors) => errors.iter().for_each(|e| { e.print() }),
+            Self::Parse(errors) => errors.iter().for_each(|e| { e.print() }),
+            Self::Runtime(error) => error.print()
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => {
+                write!(f, "failed to read `{path}` ({error})")
+            },
+            Self::Lexical(errors) => describe(f, errors),
+            Self::Parse(errors) => describe(f, errors),
+            Self::Runtime(error) => describe(f, std::slice::from_ref(error.as_ref()))
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<RuntimeError> for Error {
+    fn from(error: RuntimeError) -> Self {
+        Self::Runtime(Box::new(error))
+    }
+}
+
+fn describe(f: &mut fmt::Formatter<'_>, errors: &[impl DescribableError]) -> fmt::Result {
+    for error in errors {
+        writeln!(f, "{}: {}\n{}", error.kind(), error.message(), error.snippet())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+mod tests;
+mod engine;
+mod error;
+
+pub use engine::Engine;
+pub use error::Error;
@@ -0,0 +1,381 @@
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, cell::Cell, env, fs, path::PathBuf};
+    use crate::{engine::{Engine, Error}, interpreter::Value, errors::DescribableError};
+
+    #[test]
+    fn eval_returns_value_of_last_statement() {
+        let mut engine = Engine::new();
+        let value = engine.eval("let a <- 2; a * 21;").unwrap();
+
+        assert_eq!(value, Value::Int(42));
+    }
+
+    #[test]
+    fn definitions_persist_between_evaluations() {
+        let mut engine = Engine::new();
+        engine.eval("func square(x) -> x * x;").unwrap();
+
+        let value = engine.call_function("square", vec![Value::Int(4)]).unwrap();
+
+        assert_eq!(value, Value::Int(16));
+    }
+
+    #[test]
+    fn set_and_get_globals() {
+        let mut engine = Engine::new();
+        engine.set_global("limit", Value::Int(10));
+        engine.eval("let doubled <- limit * 2;").unwrap();
+        engine.set_global("limit", Value::Int(20));
+
+        assert_eq!(engine.get_global("doubled"), Some(Value::Int(20)));
+        assert_eq!(engine.get_global("limit"), Some(Value::Int(20)));
+        assert_eq!(engine.get_global("missing"), None);
+    }
+
+    #[test]
+    fn errors_are_returned() {
+        let mut engine = Engine::new();
+
+        assert!(matches!(engine.eval("let a <- \"a"), Err(Error::Lexical(_))));
+        assert!(matches!(engine.eval("let a <- ;"), Err(Error::Parse(_))));
+        assert!(matches!(engine.eval("print 1 / 0;"), Err(Error::Runtime(_))));
+        assert!(matches!(
+            engine.call_function("missing", vec![]),
+            Err(Error::Runtime(_))
+        ));
+    }
+
+    #[test]
+    fn native_function_is_callable_from_scripts() {
+        let mut engine = Engine::new();
+        engine.register_fn("add", 2, |args| match (&args[0], &args[1]) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+            _ => Err("expected integers".into())
+        });
+
+        assert_eq!(engine.eval("add(2, 3) * 2;").unwrap(), Value::Int(10));
+        assert_eq!(
+            engine.call_function("add", vec![Value::Int(1), Value::Int(1)]).unwrap(),
+            Value::Int(2)
+        );
+    }
+
+    #[test]
+    fn native_function_errors_are_runtime_errors() {
+        let mut engine = Engine::new();
+        engine.register_fn("fail", 0, |_| Err("out of order".into()));
+
+        let caught = engine.eval("
+            let kind <- null;
+            try { fail(); } catch e { kind <- e[\"kind\"]; }
+            kind;
+        ").unwrap();
+
+        assert_eq!(caught, Value::string("NativeError"));
+        assert!(matches!(engine.eval("fail();"), Err(Error::Runtime(_))));
+        assert!(matches!(engine.eval("fail(1);"), Err(Error::Runtime(_))));
+    }
+
+    #[test]
+    fn arity_mismatch_is_runtime_error() {
+        let mut engine = Engine::new();
+        engine.eval("let add <- func (a, b) -> a + b;").unwrap();
+
+        for call in ["add(1);", "add(1, 2, 3);"] {
+            match engine.eval(call) {
+                Err(Error::Runtime(error)) => assert_eq!(error.tag.kind(), "ArityMismatch"),
+                _ => panic!("`{call}` must fail with arity mismatch")
+            }
+        }
+    }
+
+    #[test]
+    fn arity_mismatch_is_reported_before_execution() {
+        let mut engine = Engine::new();
+        engine.set_global("ran", Value::Boolean(false));
+
+        let result = engine.eval("
+            func add(a, b) -> a + b;
+            ran <- true;
+            add(1);
+        ");
+
+        assert!(matches!(result, Err(Error::Runtime(_))));
+        assert_eq!(engine.get_global("ran"), Some(Value::Boolean(false)));
+
+        // Shadowed name may refer to another function
+        let value = engine.eval("
+            func twice(x) -> x * 2;
+            func apply() -> { let twice <- func (a, b) -> a + b; return twice(1, 2); }
+            apply();
+        ").unwrap();
+
+        assert_eq!(value, Value::Int(3));
+    }
+
+    #[test]
+    fn default_rest_and_named_arguments() {
+        let mut engine = Engine::new();
+        engine.eval("
+            func connect(host, port <- 80, secure <- port == 443, ...options) ->
+                [host, port, secure, len(options)];
+        ").unwrap();
+
+        let list = |items: Vec<Value>| Value::List(std::rc::Rc::new(items.into()));
+        let host = || Value::string("host");
+
+        assert_eq!(
+            engine.eval("connect(\"host\");").unwrap(),
+            list(vec![host(), Value::Int(80), Value::Boolean(false), Value::Int(0)])
+        );
+        assert_eq!(
+            engine.eval("connect(\"host\", 443, false, 1, 2);").unwrap(),
+            list(vec![host(), Value::Int(443), Value::Boolean(false), Value::Int(2)])
+        );
+        assert_eq!(
+            engine.eval("connect(port: 443, host: \"host\");").unwrap(),
+            list(vec![host(), Value::Int(443), Value::Boolean(true), Value::Int(0)])
+        );
+    }
+
+    #[test]
+    fn invalid_named_arguments_are_runtime_errors() {
+        let mut engine = Engine::new();
+        engine.eval("func f(a, b <- 2) -> a + b;").unwrap();
+
+        let calls = [
+            ("f(1, a: 2);", "DuplicateArgument"),
+            ("f(1, c: 2);", "UnknownArgument"),
+            ("f(b: 1);", "MissingArgument"),
+            ("f(1, 2, 3);", "ArityMismatch")
+        ];
+
+        for (call, kind) in calls {
+            match engine.eval(call) {
+                Err(Error::Runtime(error)) => assert_eq!(error.tag.kind(), kind),
+                _ => panic!("`{call}` must fail with {kind}")
+            }
+        }
+    }
+
+    #[test]
+    fn dropped_engine_frees_its_values() {
+        let marker = Rc::new(());
+        let mut engine = Engine::new();
+
+        let captured = marker.clone();
+        engine.register_fn("marker", 0, move |_| {
+            let _ = &captured;
+            Ok(Value::Null)
+        });
+
+        engine.eval("
+            func f() -> 1;
+            func counter() -> {
+                let n <- 0;
+                func next() -> { n += 1; return n; }
+                return next;
+            }
+            let next <- counter();
+            let objects <- [{\"next\": next}, func () -> marker()];
+
+            class Holder {
+                let item <- null;
+                func get() -> self.item;
+            }
+
+            let holder <- Holder();
+            holder.item <- holder.get;
+        ").unwrap();
+
+        assert_eq!(Rc::strong_count(&marker), 2);
+        drop(engine);
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
+
+    /// Write the files into the new directory for the test,
+    /// returning the path of the directory
+    fn scripts(test: &str, files: &[(&str, &[u8])]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("novis-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        dir
+    }
+
+    fn run_file(dir: &PathBuf, path: &str) -> (Engine, Result<Value, Error>) {
+        let mut engine = Engine::new();
+        let value = engine.eval_file(dir.join(path).to_str().unwrap());
+
+        (engine, value)
+    }
+
+    fn runtime_error_kind(result: Result<Value, Error>) -> &'static str {
+        match result {
+            Err(Error::Runtime(error)) => error.tag.kind(),
+            other => panic!("expected runtime error, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn modules_are_resolved_relative_to_importer() {
+        let dir = scripts("relative", &[
+            ("main.novis", b"import \"lib/shapes.novis\"; let area <- shapes.square(3);"),
+            ("lib/shapes.novis", b"import math; func square(x) -> math.mul(x, x);"),
+            ("lib/math.novis", b"func mul(a, b) -> a * b;"),
+            ("math.novis", b"func mul(a, b) -> a + b;")
+        ]);
+        let (engine, value) = run_file(&dir, "main.novis");
+
+        value.unwrap();
+        assert_eq!(engine.get_global("area"), Some(Value::Int(9)));
+        assert_eq!(runtime_error_kind(Engine::new().eval("import missing_module;")), "ModuleNotFound");
+    }
+
+    #[test]
+    fn modules_are_searched_in_search_path() {
+        let dir = scripts("search-path", &[
+            ("main/main.novis", b"import searched; let answer <- searched.answer;"),
+            ("first/other.novis", b""),
+            ("second/searched.novis", b"let answer <- 42;")
+        ]);
+        let mut engine = Engine::new();
+        engine.set_search_path(vec![dir.join("first"), dir.join("second")]);
+
+        let value = engine.eval_file(dir.join("main/main.novis").to_str().unwrap());
+
+        value.unwrap();
+        assert_eq!(engine.get_global("answer"), Some(Value::Int(42)));
+    }
+
+    #[test]
+    fn modules_are_executed_once() {
+        let dir = scripts("cache", &[
+            ("main.novis", b"import items; push(items.list, 1); import user; let seen <- user.seen;"),
+            ("user.novis", b"import \"items.novis\"; let seen <- len(items.list);"),
+            ("items.novis", b"let list <- [0];")
+        ]);
+        let (engine, value) = run_file(&dir, "main.novis");
+
+        value.unwrap();
+        assert_eq!(engine.get_global("seen"), Some(Value::Int(2)));
+    }
+
+    #[test]
+    fn module_properties_are_read_only() {
+        let dir = scripts("read-only", &[
+            ("main.novis", b"import config; config.debug <- true;"),
+            ("config.novis", b"let debug <- false;")
+        ]);
+
+        assert_eq!(runtime_error_kind(run_file(&dir, "main.novis").1), "ReadOnlyModule");
+    }
+
+    #[test]
+    fn circular_imports_are_reported() {
+        let dir = scripts("cycle", &[
+            ("main.novis", b"runs(); import a;"),
+            ("a.novis", b"import b;"),
+            ("b.novis", b"import main;"),
+            ("self.novis", b"import self;")
+        ]);
+        let runs = Rc::new(Cell::new(0));
+        let mut engine = Engine::new();
+
+        let counter = runs.clone();
+        engine.register_fn("runs", 0, move |_| {
+            counter.set(counter.get() + 1);
+            Ok(Value::Null)
+        });
+
+        let error = match engine.eval_file(dir.join("main.novis").to_str().unwrap()) {
+            Err(Error::Runtime(error)) => error,
+            other => panic!("expected runtime error, got {other:?}")
+        };
+
+        // The import of the entry file is the one closing the cycle
+        assert_eq!(runs.get(), 1);
+        assert_eq!(error.tag.kind(), "CircularImport");
+        assert!(error.span.start.info.fname.ends_with("b.novis"));
+        assert_eq!(runtime_error_kind(run_file(&dir, "self.novis").1), "CircularImport");
+    }
+
+    #[test]
+    fn unreadable_module_is_catchable() {
+        let dir = scripts("unreadable", &[
+            ("main.novis", b"let kind <- null; try { import bad; } catch e { kind <- e[\"kind\"]; }"),
+            ("bad.novis", b"\xff\xfe")
+        ]);
+        let (engine, value) = run_file(&dir, "main.novis");
+
+        value.unwrap();
+        assert_eq!(engine.get_global("kind"), Some(Value::string("InvalidModule")));
+    }
+
+    #[test]
+    fn eval_each_passes_expression_values() {
+        let mut engine = Engine::new();
+        let mut values = vec![];
+
+        let last = engine.eval_each("1; let a <- 2; a + 1; a <- 5; if a > 1 { a } null;", |value| {
+            values.push(value.clone())
+        });
+
+        assert_eq!(last.unwrap(), Value::Null);
+        assert_eq!(values, vec![Value::Int(1), Value::Int(3), Value::Null]);
+
+        values.clear();
+        let result = engine.eval_each("4; 1 / 0; 5;", |value| values.push(value.clone()));
+
+        assert!(matches!(result, Err(Error::Runtime(_))));
+        assert_eq!(values, vec![Value::Int(4)]);
+    }
+
+    #[test]
+    fn host_calls_accept_any_function_name() {
+        let mut engine = Engine::new();
+
+        for name in ["a b", "x\"", "", "1 +\n"] {
+            match engine.call_function(name, vec![]) {
+                Err(Error::Runtime(error)) => {
+                    assert_eq!(error.tag.kind(), "FunctionNotDefined");
+                    assert_eq!(error.span.start.info.fname, "<host>");
+                    assert!(error.snippet().contains(name.trim_end()));
+                },
+                other => panic!("expected runtime error, got {other:?}")
+            }
+        }
+    }
+
+    #[test]
+    fn host_values_are_built_and_read() {
+        let mut engine = Engine::new();
+        let config = Value::from_entries([
+            (Value::string("name"), Value::string("novis")),
+            (Value::string("sizes"), Value::list(vec![Value::Int(1), Value::Int(2)])),
+            (Value::Int(1), Value::Boolean(true))
+        ]).unwrap();
+
+        engine.set_global("config", config);
+        engine.eval("push(config[\"sizes\"], 3); config[1.0] <- false;").unwrap();
+
+        let config = engine.get_global("config").unwrap();
+        let entries = config.entries().unwrap();
+
+        assert_eq!(entries[0].1.as_str(), Some("novis"));
+        assert_eq!(entries[1].1.items().unwrap().len(), 3);
+        assert_eq!(entries[2], (Value::Int(1), Value::Boolean(false)));
+        assert_eq!(entries[2].1.as_bool(), Some(false));
+        assert_eq!(Value::Int(1).as_int(), Some(1));
+        assert_eq!(Value::Int(1).as_str(), None);
+        assert!(Value::Null.is_null());
+        assert!(Value::from_entries([(Value::list(vec![]), Value::Null)]).is_err());
+        assert!(Value::from_entries([(Value::Number(f64::NAN), Value::Null)]).is_err());
+    }
+}
@@ -122,7 +122,7 @@ impl Highlighter {
     /// Util function for creating underlines.
     ///
     /// # Examples
-    /// ```
+    /// ```ignore
     /// let foo = Highlighter::underline('~', 1, 10);
     /// assert_eq!(foo, "~~~~~~~~~^".to_string().red());
     /// ```
@@ -140,7 +140,7 @@ struct LineFormatter;
 impl LineFormatter {
     /// Line formatting util.
     /// # Examples
-    /// ```
+    /// ```ignore
     /// let line = String::from("foo(\"some code\");")
     /// let fmt_line = LineFormatter::new(1, &line, None);
     /// println!(fmt_line); // 1 | foo("some code");
@@ -1,3 +0,0 @@
-mod file_stream;
-
-pub use file_stream::FileStream;
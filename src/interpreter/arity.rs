@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use crate::{
+    parser::ast::{
+        expression::{Expression, ExpressionNode, PrimaryNode},
+        statement::*,
+        pattern::Pattern
+    },
+    interpreter::runtime_exception::{
+        RuntimeError,
+        RuntimeErrorTag::ArityMismatch
+    },
+    lexer::token::Token,
+    errors::Span
+};
+
+/// Report the first call of a named function with the wrong number
+/// of arguments, before any of the statements is executed.
+///
+/// Only the functions defined by the top-level `func` statements are checked,
+/// and only the calls passing all the arguments by position,
+/// as long as their names are not bound anywhere else in the program,
+/// so that every call by the name surely refers to the definition.
+pub fn check_arity(statements: &[Statement]) -> Result<(), RuntimeError> {
+    let mut names = Names::default();
+
+    for stmt in statements {
+        names.statement(stmt);
+    }
+
+    let functions: HashMap<String, &Func> = statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Func(func) => Some(func),
+            _ => None
+        })
+        .filter(|func| names.bound[&func.name.get_lexeme()] == 1)
+        .map(|func| (func.name.get_lexeme(), func))
+        .collect();
+
+    for (name, got, span) in names.calls {
+        let Some(func) = functions.get(&name.get_lexeme()) else {
+            continue;
+        };
+
+        let expected = if got < func.params.required() {
+            func.params.required()
+        } else if func.params.rest.is_none() && got > func.params.params.len() {
+            func.params.params.len()
+        } else {
+            continue;
+        };
+
+        return Err(RuntimeError {
+            span: span,
+            tag: ArityMismatch {
+                expected: expected,
+                got: got,
+                definition: Some(func.name.clone().into())
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Names bound and called in the program
+#[derive(Default)]
+struct Names {
+    /// How many times each name is bound, by any kind of definition
+    bound: HashMap<String, usize>,
+    /// Calls by a name with the number of arguments and the span of the call,
+    /// in the source order. Calls with named arguments are left to the runtime.
+    calls: Vec<(Token, usize, Span)>
+}
+
+impl Names {
+    fn bind(&mut self, name: &Token) {
+        *self.bound.entry(name.get_lexeme()).or_default() += 1;
+    }
+
+    fn group(&mut self, group: &Group) {
+        for stmt in &group.stmts {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Group(group) => self.group(group),
+            Statement::Expr(ExprStatment { expr })
+                | Statement::Retrun(Return { keyword: _, expr })
+                | Statement::Throw(Throw { keyword: _, expr })
+                | Statement::Print(Print { keyword: _, expr }) => self.expression(expr),
+            Statement::Break(_) | Statement::Continue(_) => (),
+            Statement::Try(stmt) => {
+                self.group(&stmt.body);
+
+                if let Some(catch) = &stmt.catch {
+                    self.bind(&catch.name);
+                    self.group(&catch.body);
+                }
+
+                if let Some(finally) = &stmt.finally {
+                    self.group(finally);
+                }
+            },
+            Statement::Let(stmt) => self.define(stmt),
+            Statement::Func(func) => self.func(func),
+            Statement::Cond(cond) => self.cond(cond),
+            Statement::Match(stmt) => self.r#match(stmt),
+            Statement::Loop(Loop { keyword: _, condition, body })
+                | Statement::RepeatUntil(RepeatUntil { keyword: _, body, condition })
+                | Statement::Repeat(Repeat { keyword: _, count: condition, body }) => {
+                self.expression(condition);
+                self.group(body);
+            },
+            Statement::Assign(stmt) => {
+                match stmt.target.get_node() {
+                    ExpressionNode::Primary(PrimaryNode::Identifier(name)) => {
+                        self.bind(name)
+                    },
+                    _ => self.expression(&stmt.target)
+                }
+
+                self.expression(&stmt.expr);
+            },
+            Statement::Class(class) => {
+                self.bind(&class.name);
+
+                for field in class.fields.iter() {
+                    self.define(field);
+                }
+
+                for method in &class.methods {
+                    self.func(method);
+                }
+            },
+            Statement::Import(import) => self.bind(&import.module)
+        }
+    }
+
+    fn define(&mut self, stmt: &Let) {
+        self.bind(&stmt.name);
+        self.expression(&stmt.expr);
+    }
+
+    fn func(&mut self, func: &Func) {
+        self.bind(&func.name);
+        self.params(&func.params);
+        self.group(&func.body);
+    }
+
+    fn params(&mut self, params: &Params) {
+        for param in &params.params {
+            self.bind(&param.name);
+
+            if let Some(default) = &param.default {
+                self.expression(default);
+            }
+        }
+
+        if let Some(rest) = &params.rest {
+            self.bind(rest);
+        }
+    }
+
+    fn cond(&mut self, cond: &Cond) {
+        self.expression(&cond.condition);
+        self.group(&cond.if_block);
+
+        if let Some(block) = &cond.else_block {
+            self.group(block);
+        }
+    }
+
+    fn r#match(&mut self, stmt: &Match) {
+        self.expression(&stmt.scrutinee);
+
+        for arm in &stmt.arms {
+            self.pattern(&arm.pattern);
+
+            if let Some(guard) = &arm.guard {
+                self.expression(guard);
+            }
+
+            self.expression(&arm.body);
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard(_) | Pattern::Literal(_) | Pattern::Range { .. } => (),
+            Pattern::Binding(name) => self.bind(name),
+            Pattern::List { lsquare: _, items, rsquare: _ } => {
+                items.iter().for_each(|item| self.pattern(item))
+            },
+            Pattern::Record { class: _, lcurly: _, fields, rcurly: _ } => {
+                for field in fields {
+                    match &field.pattern {
+                        Some(pattern) => self.pattern(pattern),
+                        None => self.bind(&field.key)
+                    }
+                }
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr.get_node() {
+            ExpressionNode::Primary(node) => self.primary(node),
+            ExpressionNode::Unary(node) => self.expression(&node.left),
+            ExpressionNode::Binary(node) => {
+                self.expression(&node.left);
+                self.expression(&node.right);
+            },
+            ExpressionNode::Block(group) => self.group(group),
+            ExpressionNode::If(cond) => self.cond(cond),
+            ExpressionNode::Match(stmt) => self.r#match(stmt)
+        }
+    }
+
+    fn primary(&mut self, node: &PrimaryNode) {
+        match node {
+            PrimaryNode::Literal(_) | PrimaryNode::Identifier(_) => (),
+            PrimaryNode::Paren { lparen: _, rparen: _, expr } => self.expression(expr),
+            PrimaryNode::List { lsquare: _, items, rsquare: _ } => {
+                items.iter().for_each(|item| self.expression(item))
+            },
+            PrimaryNode::Interpolation { start: _, parts } => {
+                parts.iter().for_each(|(expr, _)| self.expression(expr))
+            },
+            PrimaryNode::Map { lcurly: _, entries, rcurly: _ } => {
+                for (key, value) in entries {
+                    self.expression(key);
+                    self.expression(value);
+                }
+            },
+            PrimaryNode::Property { object, name: _ } => self.expression(object),
+            PrimaryNode::Index { object, index, rsquare: _ } => {
+                self.expression(object);
+                self.expression(index);
+            },
+            PrimaryNode::Call { callee, args, rparen } => {
+                let named = args.iter().any(|arg| arg.name.is_some());
+
+                match callee.get_node() {
+                    ExpressionNode::Primary(PrimaryNode::Identifier(_)) if named => (),
+                    ExpressionNode::Primary(PrimaryNode::Identifier(name)) => {
+                        self.calls.push((name.clone(), args.len(), Span {
+                            start: name.clone(),
+                            end: rparen.clone()
+                        }))
+                    },
+                    _ => self.expression(callee)
+                }
+
+                args.iter().for_each(|arg| self.expression(&arg.expr));
+            },
+            PrimaryNode::Lambda { keyword: _, params, body } => {
+                self.params(params);
+                self.group(body);
+            }
+        }
+    }
+}
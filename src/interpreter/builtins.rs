@@ -0,0 +1,148 @@
+use std::{rc::Rc, cell::RefCell};
+use super::{
+    env::Env,
+    value::{Value, NativeFunction},
+    map::Map,
+    runtime_exception::RuntimeErrorTag::{self, *}
+};
+
+type Builtin = fn(Vec<Value>) -> Result<Value, RuntimeErrorTag>;
+
+/// Functions available in every program, with their arity
+const BUILTINS: &[(&str, usize, Builtin)] = &[
+    ("len", 1, len),
+    ("push", 2, push),
+    ("pop", 1, pop),
+    ("insert", 3, insert),
+    ("remove", 2, remove),
+    ("slice", 3, slice),
+    ("keys", 1, keys),
+    ("has", 2, has),
+];
+
+/// Global environment programs and modules are executed in.
+/// Built-in functions live in the enclosing scope,
+/// so programs are free to redefine them.
+pub fn global_env(mut env: Env) -> Env {
+    for (name, arity, func) in BUILTINS {
+        let native = NativeFunction {
+            name: name.to_string(),
+            arity: *arity,
+            func: Rc::new(func)
+        };
+
+        env.define(&name.to_string(), Value::NativeFunction(native))
+            .expect("built-in function is defined twice");
+    }
+
+    env.enter();
+    env
+}
+
+/// `len(xs)` - number of elements in the list, entries in the map
+/// or characters in the string
+fn len(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    match &args[0] {
+        Value::List(list) => Ok(Value::Int(list.borrow().len() as i64)),
+        Value::Map(map) => Ok(Value::Int(map.borrow().len() as i64)),
+        Value::String(str) => Ok(Value::Int(str.chars().count() as i64)),
+        value => Err(unexpected_type("list, map or string", value))
+    }
+}
+
+/// `push(xs, item)` - append item to the end of the list
+fn push(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let list = expect_list(&args[0])?;
+    list.borrow_mut().push(args[1].clone());
+
+    Ok(Value::Null)
+}
+
+/// `pop(xs)` - remove the last item of the list and return it
+fn pop(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let list = expect_list(&args[0])?;
+    let item = list.borrow_mut().pop();
+
+    item.ok_or(InvalidArgument {
+        message: "cannot pop from an empty list".into()
+    })
+}
+
+/// `insert(xs, index, item)` - insert item before the `index`,
+/// index equal to the list length appends the item
+fn insert(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let list = expect_list(&args[0])?;
+    let mut list = list.borrow_mut();
+    let index = args[1].to_index(list.len() + 1)?;
+
+    list.insert(index, args[2].clone());
+    Ok(Value::Null)
+}
+
+/// `remove(xs, index)` - remove item at the `index` and return it,
+/// for maps `index` is the key of the entry to remove
+fn remove(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    match &args[0] {
+        Value::List(list) => {
+            let mut list = list.borrow_mut();
+            let index = args[1].to_index(list.len())?;
+
+            Ok(list.remove(index))
+        },
+        Value::Map(map) => map.borrow_mut().remove(&args[1]),
+        value => Err(unexpected_type("list or map", value))
+    }
+}
+
+/// `slice(xs, start, end)` - new list with items from `start` up to,
+/// but not including `end`
+fn slice(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let list = expect_list(&args[0])?;
+    let list = list.borrow();
+    let start = args[1].to_index(list.len() + 1)?;
+    let end = args[2].to_index(list.len() + 1)?;
+
+    if start > end {
+        return Err(InvalidArgument {
+            message: format!("slice start {start} is greater than end {end}")
+        });
+    }
+
+    Ok(Value::list(list[start..end].to_vec()))
+}
+
+/// `keys(m)` - list of the map keys in insertion order
+fn keys(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let map = expect_map(&args[0])?;
+    let keys = map.borrow().keys();
+
+    Ok(Value::list(keys))
+}
+
+/// `has(m, key)` - whether the map contains the key
+fn has(args: Vec<Value>) -> Result<Value, RuntimeErrorTag> {
+    let map = expect_map(&args[0])?;
+    let contains = map.borrow().contains(&args[1])?;
+
+    Ok(Value::Boolean(contains))
+}
+
+fn expect_map(value: &Value) -> Result<Rc<RefCell<Map>>, RuntimeErrorTag> {
+    match value {
+        Value::Map(map) => Ok(map.clone()),
+        value => Err(unexpected_type("map", value))
+    }
+}
+
+fn expect_list(value: &Value) -> Result<Rc<RefCell<Vec<Value>>>, RuntimeErrorTag> {
+    match value {
+        Value::List(list) => Ok(list.clone()),
+        value => Err(unexpected_type("list", value))
+    }
+}
+
+fn unexpected_type(expected: &str, value: &Value) -> RuntimeErrorTag {
+    InvalidArgument {
+        message: format!("expected {expected}, got {}", value.type_name())
+    }
+}
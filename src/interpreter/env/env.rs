@@ -1,35 +1,62 @@
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug},
+    rc::Rc
+};
 use crate::interpreter::{
     value::Value,
-    runtime_exception::{
-        RuntimeError,
-        RuntimeErrorTag::{*, self}
-    }
+    module::Modules,
+    runtime_exception::RuntimeErrorTag::{*, self}
 };
 
-#[derive(Clone, Debug, PartialEq)]
+/// Environment is a chain of scopes, where each scope refers to its parent.
+///
+/// Scopes are reference-counted, so cloning the environment is cheap
+/// and all the clones share the same variables. That's how functions
+/// capture the environment they are defined in.
+#[derive(Clone)]
 pub struct Env {
-    inner: Vec<EnvInner>,
-    level: usize
+    scope: Rc<RefCell<Scope>>,
+    /// Modules imported by the program, shared by all of its environments
+    modules: Rc<RefCell<Modules>>
 }
 
 impl Env {
     pub fn new() -> Self {
         Self {
-            inner: vec![EnvInner {
+            scope: Rc::new(RefCell::new(Scope {
+                hashmap: HashMap::new(),
+                parent: None
+            })),
+            modules: Rc::new(RefCell::new(Modules::default()))
+        }
+    }
+
+    /// New environment of the same program, which doesn't share
+    /// any scope with the current one
+    pub fn fork(&self) -> Self {
+        Self {
+            scope: Rc::new(RefCell::new(Scope {
                 hashmap: HashMap::new(),
-            }],
-            level: 0
+                parent: None
+            })),
+            modules: self.modules.clone()
         }
     }
 
-    /// Enter new level of environment
+    pub fn modules(&self) -> Rc<RefCell<Modules>> {
+        self.modules.clone()
+    }
+
+    /// Enter new scope, nested into the current one
     pub fn enter(&mut self) -> &mut Self {
-        self.inner.push(EnvInner {
-            hashmap: HashMap::new(),
-        });
+        let parent = self.clone();
 
-        self.level += 1;
+        self.scope = Rc::new(RefCell::new(Scope {
+            hashmap: HashMap::new(),
+            parent: Some(parent)
+        }));
 
         return self
     }
@@ -39,12 +66,12 @@ impl Env {
     /// # Panics
     /// Panics if already at the global scope.
     pub fn leave(&mut self) {
-        if self.level == 0 {
-            panic!("internal environment error (negative level)");
-        }
+        let parent = self.scope.borrow().parent.clone();
 
-        self.inner.pop();
-        self.level -= 1;
+        match parent {
+            Some(parent) => *self = parent,
+            None => panic!("internal environment error (negative level)")
+        }
     }
 
     /// Set variable value in environment.
@@ -52,78 +79,131 @@ impl Env {
     /// it contains.
     ///
     /// # Errors
-    /// If variable does not exists, emits RuntimeError.
-    pub fn set(&mut self, name: &String, value: Value) -> Result<(), RuntimeError> {
-        let mut level = (self.inner.len() as i32) -1;
-
-        while level >= 0 {
-            if let Some(local) = self.inner[level as usize].get_mut(name) {
-                *local = value.clone();
-            }
+    /// If variable does not exists, emits `NameNotDefined`.
+    pub fn set(&mut self, name: &String, value: Value) -> Result<(), RuntimeErrorTag> {
+        let mut scope = self.scope.borrow_mut();
 
-            level -= 1;
+        if let Some(local) = scope.hashmap.get_mut(name) {
+            *local = value;
+            return Ok(());
         }
 
-        Ok(())
+        match &mut scope.parent {
+            Some(parent) => parent.set(name, value),
+            None => Err(NameNotDefined {
+                name: name.into()
+            })
+        }
     }
 
     /// Get variable in environment.
-    /// It will automaticly find the value in all the levels.
-    pub fn get(&self, name: &String) -> Option<&Value> {
-        let mut env_iter = self.inner.iter().rev();
-        let mut current = env_iter.next();
-
-        while current.is_some() {
-            let inner = current.expect(
-                &format!("internal error at level {} of environment", self.level)
-            );
-
-            if let Some(value) = inner.get(name) {
-                return Some(value);
-            }
+    /// It will automaticly find the value in all the enclosing scopes.
+    pub fn get(&self, name: &String) -> Option<Value> {
+        let scope = self.scope.borrow();
 
-            current = env_iter.next();
+        match scope.hashmap.get(name) {
+            Some(value) => Some(value.clone()),
+            None => scope.parent.as_ref()?.get(name)
         }
+    }
 
-        None
+    /// Get the value from the current scope only
+    pub fn get_local(&self, name: &String) -> Option<Value> {
+        self.scope.borrow().hashmap.get(name).cloned()
     }
 
-    /// Define value in current level of environment.
+    /// Define value in current scope of environment.
     pub fn define(&mut self, name: &String, value: Value) -> Result<(), RuntimeErrorTag> {
-        self.get_current_mut().define(name, value)?;
-        Ok(())
-    }
+        let mut scope = self.scope.borrow_mut();
 
-    fn get_current_mut(&mut self) -> &mut EnvInner {
-        self.inner.get_mut(self.level).unwrap()
+        if scope.hashmap.contains_key(name) {
+            return Err(NameRedefinition {
+                name: name.into()
+            });
+        }
+
+        scope.hashmap.insert(name.into(), value);
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
- struct EnvInner {
-    hashmap: HashMap<String, Value>,
-}
+impl Env {
+    /// Free the scopes reachable from the environment.
+    ///
+    /// Functions capture the scope they are defined in, while the scope
+    /// usually holds the functions in turn. Such reference cycles are never
+    /// freed by counting, so all the scopes reachable from this one through
+    /// the values, closures and imported modules are cleared instead.
+    /// Values taken out of the environment before stay valid, but functions
+    /// among them lose their variables.
+    pub fn release(&self) {
+        let mut scopes = vec![self.scope.clone()];
+        let mut values: Vec<Value> = vec![];
+        let mut visited: HashSet<*const ()> = HashSet::new();
+
+        for module in self.modules.borrow_mut().unload() {
+            scopes.push(module.env.scope.clone());
+        }
 
-impl EnvInner {
-    /// Get value of this environment level
-    pub fn get(&self, name: &String) -> Option<&Value> {
-        self.hashmap.get(name)
-    }
+        // Values are kept until the end, so nothing is freed
+        // while the scopes are still borrowed
+        let mut next = 0;
 
-    /// Define value in this environment level
-    pub fn define(&mut self, name: &String, value: Value) -> Result<(), RuntimeErrorTag> {
-        if self.get(name).is_none() {
-            self.hashmap.insert(name.into(), value);
-            Ok(())
-        } else {
-            Err(NameRedefinition {
-                name: name.into()
-            })
+        loop {
+            if let Some(scope) = scopes.pop() {
+                if !visited.insert(Rc::as_ptr(&scope) as *const ()) {
+                    continue;
+                }
+
+                let mut scope = scope.borrow_mut();
+                values.extend(std::mem::take(&mut scope.hashmap).into_values());
+
+                if let Some(parent) = scope.parent.take() {
+                    scopes.push(parent.scope);
+                }
+
+                continue;
+            }
+
+            let Some(value) = values.get(next).cloned() else {
+                break;
+            };
+
+            next += 1;
+
+            match value {
+                Value::Function { closure, .. } => scopes.push(closure.scope),
+                Value::Class(class) => {
+                    scopes.push(class.closure.scope.clone());
+                    values.extend(class.methods.iter().map(|(_, method)| method.clone()));
+                },
+                Value::Instance(instance) if visited.insert(Rc::as_ptr(&instance) as *const ()) => {
+                    let instance = instance.borrow();
+                    values.push(Value::Class(instance.class.clone()));
+                    values.extend(instance.fields.iter().map(|(_, field)| field.clone()));
+                },
+                Value::List(list) if visited.insert(Rc::as_ptr(&list) as *const ()) => {
+                    values.extend(list.borrow().iter().cloned());
+                },
+                Value::Map(map) if visited.insert(Rc::as_ptr(&map) as *const ()) => {
+                    values.extend(map.borrow().entries().iter().map(|(_, v)| v.clone()));
+                },
+                Value::Module(module) => scopes.push(module.env.scope.clone()),
+                _ => ()
+            }
         }
     }
+}
 
-    /// Get value as mutable
-    pub fn get_mut(&mut self, name: &String) -> Option<&mut Value> {
-        self.hashmap.get_mut(name)
+// Scopes may contain functions capturing them,
+// so the derived implementation would never end
+impl Debug for Env {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Env")
     }
 }
+
+struct Scope {
+    hashmap: HashMap<String, Value>,
+    parent: Option<Env>
+}
@@ -7,10 +7,13 @@ use crate::{
                 *
             },
             RuntimeError,
-            RuntimeErrorTag::*
+            RuntimeErrorTag::{
+                self,
+                *
+            }
         },
-        value::Value, env::Env
-    }, lexer::token::TokenTag, errors::Span
+        value::{Value, OperationError}, env::Env
+    }, lexer::token::{Token, TokenTag}, errors::Span
 };
 
 use super::evaluatable::Evaluatable;
@@ -18,86 +21,176 @@ use super::evaluatable::Evaluatable;
 impl Evaluatable for BinaryNode {
     fn eval(&self, env: &mut Env) -> Result<Value, InterpreterException> {
         let left = self.left.eval(env)?;
-        let right = self.right.eval(env)?;
 
-        let val = match self.op.tag {
-            TokenTag::Plus => {
-                left + right
-            },
-            TokenTag::Minus => {
-                left - right
-            },
-            TokenTag::Star => {
-                left * right
-            },
-            TokenTag::Circ => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        Ok(Value::Number(l.powf(r)))
-                    },
-                    _ => Err(())
-                }
-            },
-            TokenTag::Slash => {
-                if right == Value::Number(0.0) {
-                    return Err(Fatal(
-                        RuntimeError {
-                            span: Span::from(self.clone()),
-                            tag: DivisionByZero
-                        }
-                    ))
-                }
-
-                left / right
-            },
-            TokenTag::EqualEqual => Ok(Value::Boolean(left == right)),
-            TokenTag::BangEqual => Ok(Value::Boolean(left != right)),
-            TokenTag::Less => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        Ok(Value::Boolean(l < r))
-                    },
-                    _ => Err(())
-                }
-            },
-            TokenTag::Greater => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        Ok(Value::Boolean(l > r))
-                    },
-                    _ => Err(())
-                }
+        // Logical operators short-circuit, so right operand
+        // is evaluated only when it affects the result
+        match self.op.tag {
+            TokenTag::And if !left.to_boolean() => {
+                return Ok(Value::Boolean(false))
             },
-            TokenTag::LessEqual => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        Ok(Value::Boolean(l <= r))
-                    },
-                    _ => Err(()),
-                }
+            TokenTag::Or if left.to_boolean() => {
+                return Ok(Value::Boolean(true))
             },
-            TokenTag::GreaterEqual => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        Ok(Value::Boolean(l >= r))
-                    },
-                    _ => Err(())
-                }
+            TokenTag::And | TokenTag::Or => {
+                return Ok(Value::Boolean(self.right.eval(env)?.to_boolean()))
             },
-            _ => unreachable!()
+            _ => ()
         };
 
-        match val {
-            Err(_) => Err(Fatal(
+        let right = self.right.eval(env)?;
+
+        match binary_op(&self.op, left, right) {
+            Err(tag) => Err(Fatal(
                 RuntimeError {
                     span: Span::from(self.clone()),
-                    tag: IncompatibleOperands {
-                        expr: self.clone(),
-                        op: self.op.clone()
-                    }
+                    tag: tag
                 }
             )),
             Ok(val) => Ok(val)
         }
     }
 }
+
+/// Apply binary operator to already evaluated operands.
+/// Compound assignment operators (`+=`, `-=`, `*=`, `/=`)
+/// are applied as their arithmetic counterparts.
+///
+/// Operations on two integers give integer, except for the division,
+/// if any of the operands is float, the result is float as well.
+/// Integer division `//` and remainder `%` are floored, so the remainder
+/// has the sign of the divisor.
+///
+/// Bitwise operators `&`, `|`, `~` (xor), `<<` and `>>` require integers.
+///
+/// # Errors
+/// Emits `DivisionByZero`, `IntegerOverflow`, `NegativeShiftCount`
+/// or `IncompatibleOperands`,
+/// it's up to the caller to provide the span of the error.
+pub fn binary_op(op: &Token, left: Value, right: Value) -> Result<Value, RuntimeErrorTag> {
+    let types = (left.type_name(), right.type_name());
+
+    let val = match op.tag {
+        TokenTag::Plus | TokenTag::PlusEqual => {
+            left + right
+        },
+        TokenTag::Minus | TokenTag::MinusEqual => {
+            left - right
+        },
+        TokenTag::Star | TokenTag::StarEqual => {
+            left * right
+        },
+        TokenTag::Circ => power(left, right),
+        TokenTag::Slash
+        | TokenTag::SlashEqual
+        | TokenTag::SlashSlash
+        | TokenTag::Percent => {
+            if right == Value::Int(0) {
+                return Err(DivisionByZero)
+            }
+
+            match op.tag {
+                TokenTag::SlashSlash => left.floor_div(right),
+                TokenTag::Percent => left % right,
+                _ => left / right
+            }
+        },
+        TokenTag::Ampersand
+        | TokenTag::Pipe
+        | TokenTag::Tilde
+        | TokenTag::LessLess
+        | TokenTag::GreaterGreater => {
+            match (left, right) {
+                (Value::Int(_), Value::Int(count))
+                    if count < 0 && matches!(op.tag, TokenTag::LessLess | TokenTag::GreaterGreater) => {
+                    return Err(NegativeShiftCount { count: count });
+                },
+                (Value::Int(l), Value::Int(r)) => bitwise(&op.tag, l, r),
+                _ => Err(OperationError::Incompatible)
+            }
+        },
+        TokenTag::EqualEqual => Ok(Value::Boolean(left == right)),
+        TokenTag::BangEqual => Ok(Value::Boolean(left != right)),
+        TokenTag::Less
+        | TokenTag::Greater
+        | TokenTag::LessEqual
+        | TokenTag::GreaterEqual => {
+            match left.compare(&right) {
+                Some(ordering) => Ok(Value::Boolean(match op.tag {
+                    TokenTag::Less => ordering.is_lt(),
+                    TokenTag::Greater => ordering.is_gt(),
+                    TokenTag::LessEqual => ordering.is_le(),
+                    _ => ordering.is_ge()
+                })),
+                // Comparison with `NaN` is always false
+                None if left.to_float().is_some() && right.to_float().is_some() => {
+                    Ok(Value::Boolean(false))
+                },
+                None => Err(OperationError::Incompatible)
+            }
+        },
+        _ => unreachable!()
+    };
+
+    val.map_err(|err| match err {
+        OperationError::Incompatible => IncompatibleOperands {
+            op: op.clone(),
+            left: types.0.into(),
+            right: types.1.into()
+        },
+        OperationError::Overflow => IntegerOverflow
+    })
+}
+
+/// Apply bitwise operator to integers.
+/// Left shift is checked the same way multiplication is, so it emits
+/// `IntegerOverflow` if any significant bit is lost. Right shift is
+/// arithmetic, i.e. it keeps the sign.
+fn bitwise(op: &TokenTag, l: i64, r: i64) -> Result<Value, OperationError> {
+    let val = match op {
+        TokenTag::Ampersand => l & r,
+        TokenTag::Pipe => l | r,
+        TokenTag::Tilde => l ^ r,
+        TokenTag::LessLess if r >= 64 => {
+            if l != 0 {
+                return Err(OperationError::Overflow);
+            }
+
+            0
+        },
+        TokenTag::LessLess => {
+            let shifted = l << r;
+
+            if shifted >> r != l {
+                return Err(OperationError::Overflow);
+            }
+
+            shifted
+        },
+        TokenTag::GreaterGreater => l >> r.min(63),
+        _ => unreachable!()
+    };
+
+    Ok(Value::Int(val))
+}
+
+/// Integer raised to the non-negative integer power stays integer,
+/// otherwise the result is float
+fn power(left: Value, right: Value) -> Result<Value, OperationError> {
+    match (&left, &right) {
+        (Value::Int(l), Value::Int(r)) if *r >= 0 => match l {
+            // These never overflow, however large the exponent is
+            0 => Ok(Value::Int(if *r == 0 { 1 } else { 0 })),
+            1 => Ok(Value::Int(1)),
+            -1 => Ok(Value::Int(if r % 2 == 0 { 1 } else { -1 })),
+            _ => u32::try_from(*r)
+                .ok()
+                .and_then(|r| l.checked_pow(r))
+                .map(Value::Int)
+                .ok_or(OperationError::Overflow)
+        },
+        _ => match (left.to_float(), right.to_float()) {
+            (Some(l), Some(r)) => Ok(Value::Number(l.powf(r))),
+            _ => Err(OperationError::Incompatible)
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
         runtime_exception::InterpreterException,
         value::Value,
         env::Env,
+        statement::Executable
     }
 };
 
@@ -21,6 +22,9 @@ impl Evaluatable for ExpressionNode {
             ExpressionNode::Primary(primary) => primary.eval(env),
             ExpressionNode::Unary(unary) => unary.eval(env),
             ExpressionNode::Binary(binary) => binary.eval(env),
+            ExpressionNode::Block(group) => group.run(env),
+            ExpressionNode::If(cond) => cond.run(env),
+            ExpressionNode::Match(r#match) => r#match.run(env),
         }
     }
 }
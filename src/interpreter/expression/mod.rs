@@ -4,4 +4,6 @@ mod unary;
 mod primary;
 
 pub use evaluatable::Evaluatable;
+pub use binary::binary_op;
+pub use primary::{literal, call_value};
 pub mod expression;
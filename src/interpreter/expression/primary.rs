@@ -1,5 +1,9 @@
+use std::{rc::Rc, cell::RefCell};
 use crate::{
-    parser::ast::expression::{PrimaryNode, Expression},
+    parser::ast::{
+        expression::{PrimaryNode, Expression, ExpressionNode, Arg},
+        statement::Params
+    },
     interpreter::{
         runtime_exception::{
             InterpreterException::{
@@ -10,8 +14,11 @@ use crate::{
             RuntimeErrorTag::*
         },
         value::Value,
+        map::Map,
+        object::{Class, Instance},
         env::Env,
         statement::Executable,
+        utils::{index_error, property_error},
     },
     lexer::token::{
         TokenTag,
@@ -32,84 +39,305 @@ impl Evaluatable for PrimaryNode {
                 expr
             } => paren(env, expr),
             PrimaryNode::Identifier(token) => identifier(env, token),
+            PrimaryNode::List {
+                lsquare: _,
+                items,
+                rsquare: _
+            } => list(env, items),
+            PrimaryNode::Interpolation {
+                start,
+                parts
+            } => interpolation(env, start, parts),
+            PrimaryNode::Map {
+                lcurly: _,
+                entries,
+                rcurly: _
+            } => map(env, entries),
+            PrimaryNode::Property {
+                object,
+                name
+            } => object
+                .eval(env)?
+                .get_property(&name.get_lexeme())
+                .map_err(|tag| property_error(tag, object, name)),
+            PrimaryNode::Index {
+                object,
+                index,
+                rsquare: _
+            } => {
+                let collection = object.eval(env)?;
+                let key = index.eval(env)?;
+
+                collection
+                    .get_item(&key)
+                    .map_err(|tag| index_error(tag, object, index))
+            },
             PrimaryNode::Call {
-                name,
+                callee,
                 args,
                 rparen: _
-            } => call(name, env, args),
+            } => call(callee, env, args, Span::from(self.clone())),
+            PrimaryNode::Lambda {
+                keyword: _,
+                params,
+                body
+            } => Ok(Value::Function {
+                params: params.clone(),
+                name: None,
+                body: body.clone(),
+                closure: env.clone()
+            }),
         }
     }
 }
 
 /// Evaluate function call
-fn call(name: &Token, env: &mut Env, args: &Vec<Box<Expression>>) -> Result<Value, InterpreterException> {
-    match &name.tag {
-        TokenTag::Identifier(s) => match env.get(&s) {
-            Some(Value::Function {
-                params,
-                name:_,
-                body
-            }) => {
-                let mut global_env = env.to_owned();
-                let closure = &mut global_env.enter();
-
-                for i in 0..params.len() {
-                    match &params[i].tag {
-                        TokenTag::Identifier(name) => {
-                            let definition_result = closure.define(
-                                &name,
-                                args[i].eval(&mut env.to_owned())?
-                            );
-
-                            match definition_result {
-                                Err(err_tag) => {
-                                    return Err(InterpreterException::Fatal(
-                                        RuntimeError {
-                                            span: params[i].clone().into(),
-                                            tag: err_tag
-                                        }
-                                    ))
-                                },
-                                _ => ()
-                            }
-                        },
-                        _ => unreachable!()
+fn call(
+    callee: &Box<Expression>,
+    env: &mut Env,
+    args: &Vec<Arg>,
+    span: Span
+) -> Result<Value, InterpreterException> {
+    let function = match callee.get_node() {
+        // Calling undefined name is reported specially
+        ExpressionNode::Primary(PrimaryNode::Identifier(name)) => {
+            match env.get(&name.get_lexeme()) {
+                Some(value) => value,
+                None => return Err(Fatal(
+                    RuntimeError {
+                        span: Span::from(name.clone()),
+                        tag: FunctionNotDefined { name: name.get_lexeme() }
                     }
-                }
+                ))
+            }
+        },
+        _ => callee.eval(env)?
+    };
+
+    // Arguments are evaluated in the caller's environment,
+    // but the body is executed in the one function is defined in
+    let mut values = vec![];
+    let mut named = vec![];
 
-                match body.as_ref().run(closure) {
-                    Err(InterpreterException::Return(value)) => {
-                        closure.leave();
-                        *env = global_env;
-                        return Ok(value.val);
+    for arg in args {
+        let value = arg.expr.eval(env)?;
+
+        match &arg.name {
+            Some(name) => named.push((name.clone(), value)),
+            None => values.push(value)
+        }
+    }
+
+    call_value(function, values, named, Span::from(callee.as_ref().clone()), span)
+}
+
+/// Call the value `function` with the arguments
+///
+/// # Arguments
+/// * `args` - Positional arguments
+/// * `named` - Named arguments along with their names
+/// * `callee` - Span of the function, where it's reported to be not callable
+/// * `span` - Span of the whole call, where the other errors are reported
+pub fn call_value(
+    function: Value,
+    args: Vec<Value>,
+    named: Vec<(Token, Value)>,
+    callee: Span,
+    span: Span
+) -> Result<Value, InterpreterException> {
+    match function {
+        Value::Function {
+            params,
+            name,
+            body,
+            closure
+        } => {
+            // Lambdas are pointed at by the start of their body
+            let definition = name.unwrap_or_else(|| body.lcurly.clone());
+
+            let mut local = closure;
+            local.enter();
+
+            bind_args(&mut local, &params, args, named, definition, span)?;
+
+            match body.as_ref().run(&mut local) {
+                Err(InterpreterException::Return(value)) => Ok(value.val),
+                Err(exception @ InterpreterException::Throw(_)) => Err(exception),
+                // `break` and `continue` can't escape the function
+                Err(exception) => Err(Fatal(exception.into_error())),
+                Ok(_) => Ok(Value::Null)
+            }
+        }
+        Value::NativeFunction(native) => {
+            // Native functions have no parameter names
+            if let Some((name, _)) = named.first() {
+                return Err(unknown_argument(name));
+            }
+
+            if args.len() != native.arity {
+                return Err(Fatal(RuntimeError {
+                    span: span,
+                    tag: ArityMismatch {
+                        expected: native.arity,
+                        got: args.len(),
+                        definition: None
                     }
-                    _ => { Ok(Value::Null) }
-                }
+                }));
             }
-            // Value is not a functional
-            Some(_) => return Err(Fatal(
-                RuntimeError {
-                    span: Span::from(name.clone()),
-                    tag: ObjectIsNotCallable
-                }
-            )),
-            // Nothing found
-            None => return Err(Fatal(
-                RuntimeError {
-                    span: Span::from(name.clone()),
-                    tag: FunctionNotDefined { name: name.get_lexeme() }
-                }
-            ))
+
+            (native.func)(args).map_err(|tag| Fatal(RuntimeError {
+                span: span,
+                tag: tag
+            }))
         }
-        _ => unreachable!()
+        // Calling a class creates new instance of it,
+        // arguments are passed to the `init` method
+        Value::Class(class) => {
+            let instance = instantiate(&class)?;
+
+            if class.method("init").is_some() {
+                let init = Instance::get(&instance, "init").unwrap();
+                call_value(init, args, named, callee, span)?;
+            } else if let Some((name, _)) = named.first() {
+                return Err(unknown_argument(name));
+            } else if !args.is_empty() {
+                return Err(Fatal(RuntimeError {
+                    span: span,
+                    tag: ArityMismatch {
+                        expected: 0,
+                        got: args.len(),
+                        definition: None
+                    }
+                }));
+            }
+
+            Ok(Value::Instance(instance))
+        }
+        // Value is not a functional
+        _ => Err(Fatal(
+            RuntimeError {
+                span: callee,
+                tag: ObjectIsNotCallable
+            }
+        ))
+    }
+}
+
+/// Define the function parameters in its `local` environment.
+///
+/// Positional arguments are bound in order, extra ones are collected
+/// into a list by the rest parameter. Named arguments are bound by name.
+/// Parameters left get their default values, evaluated in order,
+/// so a default may refer to the parameters before it.
+///
+/// # Arguments
+/// * `definition` - Token the function is defined at
+/// * `span` - Span of the whole call
+fn bind_args(
+    local: &mut Env,
+    params: &Params,
+    mut args: Vec<Value>,
+    named: Vec<(Token, Value)>,
+    definition: Token,
+    span: Span
+) -> Result<(), InterpreterException> {
+    let arity_mismatch = |expected, got| Fatal(RuntimeError {
+        span: span.clone(),
+        tag: ArityMismatch {
+            expected: expected,
+            got: got,
+            definition: Some(definition.clone().into())
+        }
+    });
+
+    if params.rest.is_none() && args.len() > params.params.len() {
+        return Err(arity_mismatch(params.params.len(), args.len()));
     }
+
+    let positional = args.len();
+    let rest = args.split_off(positional.min(params.params.len()));
+
+    let mut values: Vec<Option<Value>> = args.into_iter().map(Some).collect();
+    values.resize(params.params.len(), None);
+
+    let has_named = !named.is_empty();
+
+    for (name, value) in named {
+        let lexeme = name.get_lexeme();
+        let position = params.params
+            .iter()
+            .position(|param| param.name.get_lexeme() == lexeme)
+            .ok_or_else(|| unknown_argument(&name))?;
+
+        if values[position].is_some() {
+            return Err(Fatal(RuntimeError {
+                span: name.into(),
+                tag: DuplicateArgument { name: lexeme }
+            }));
+        }
+
+        values[position] = Some(value);
+    }
+
+    for (param, value) in params.params.iter().zip(values) {
+        let value = match (value, &param.default) {
+            (Some(value), _) => value,
+            (None, Some(default)) => default.eval(local)?,
+            // Missing named parameter is reported by its name
+            (None, None) if has_named => return Err(Fatal(RuntimeError {
+                span: span,
+                tag: MissingArgument { name: param.name.get_lexeme() }
+            })),
+            (None, None) => return Err(arity_mismatch(params.required(), positional))
+        };
+
+        define_param(local, &param.name, value)?;
+    }
+
+    if let Some(name) = &params.rest {
+        define_param(local, name, Value::List(Rc::new(RefCell::new(rest))))?;
+    }
+
+    Ok(())
+}
+
+fn define_param(local: &mut Env, name: &Token, value: Value) -> Result<(), InterpreterException> {
+    local.define(&name.get_lexeme(), value).map_err(|tag| Fatal(RuntimeError {
+        span: name.clone().into(),
+        tag: tag
+    }))
+}
+
+fn unknown_argument(name: &Token) -> InterpreterException {
+    Fatal(RuntimeError {
+        span: name.clone().into(),
+        tag: UnknownArgument { name: name.get_lexeme() }
+    })
+}
+
+/// Create new instance of the class, evaluating the field initializers
+/// in the environment the class is declared in
+fn instantiate(class: &Rc<Class>) -> Result<Rc<RefCell<Instance>>, InterpreterException> {
+    let mut local = class.closure.clone();
+    local.enter();
+
+    let mut fields = vec![];
+
+    for field in class.fields.iter() {
+        fields.push((field.name.get_lexeme(), field.expr.eval(&mut local)?));
+    }
+
+    Ok(Rc::new(RefCell::new(Instance {
+        class: class.clone(),
+        fields: fields
+    })))
 }
 
 /// Evaluate identifier
 fn identifier(env: &mut Env, token: &Token) -> Result<Value, InterpreterException> {
     match &token.tag {
         TokenTag::Identifier(name) => match env.get(&name) {
-            Some(val) => return Ok(val.clone()),
+            Some(val) => return Ok(val),
             None => return Err(Fatal(
                 RuntimeError {
                     span: Span::from(token.clone()),
@@ -123,11 +351,72 @@ fn identifier(env: &mut Env, token: &Token) -> Result<Value, InterpreterExceptio
     }
 }
 
+/// Evaluate list literal
+fn list(env: &mut Env, items: &Vec<Box<Expression>>) -> Result<Value, InterpreterException> {
+    let mut values = vec![];
+
+    for item in items {
+        values.push(item.eval(env)?);
+    }
+
+    Ok(Value::list(values))
+}
+
+/// Evaluate interpolated string,
+/// values of the expressions are converted to strings
+fn interpolation(
+    env: &mut Env,
+    start: &Token,
+    parts: &Vec<(Box<Expression>, Token)>
+) -> Result<Value, InterpreterException> {
+    let mut string = string_part(start);
+
+    for (expr, part) in parts {
+        string.push_str(&expr.eval(env)?.to_string()?);
+        string.push_str(&string_part(part));
+    }
+
+    Ok(Value::String(Box::new(string)))
+}
+
+fn string_part(token: &Token) -> String {
+    match &token.tag {
+        TokenTag::StringStart(s)
+        | TokenTag::StringMiddle(s)
+        | TokenTag::StringEnd(s) => s.clone(),
+        _ => unreachable!()
+    }
+}
+
+/// Evaluate map literal, the later duplicate key overrides the former
+fn map(
+    env: &mut Env,
+    entries: &Vec<(Box<Expression>, Box<Expression>)>
+) -> Result<Value, InterpreterException> {
+    let mut map = Map::new();
+
+    for (key, value) in entries {
+        let k = key.eval(env)?;
+        let v = value.eval(env)?;
+
+        map.insert(k, v).map_err(|tag| Fatal(RuntimeError {
+            span: Span::from(*key.clone()),
+            tag: tag
+        }))?;
+    }
+
+    Ok(Value::map(map))
+}
+
 /// Evaluate literal value
-fn literal(token: &Token) -> Result<Value, InterpreterException> {
+pub fn literal(token: &Token) -> Result<Value, InterpreterException> {
     let value = match token.tag.clone() {
+        TokenTag::Integer(n) => Value::Int(n),
         TokenTag::Number(n) => Value::Number(n),
         TokenTag::String(s) => Value::String(Box::new(s.into())),
+        TokenTag::True => Value::Boolean(true),
+        TokenTag::False => Value::Boolean(false),
+        TokenTag::Null => Value::Null,
         _ => unreachable!()
     };
 
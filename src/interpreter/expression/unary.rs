@@ -9,7 +9,7 @@ use crate::{
             RuntimeError,
             RuntimeErrorTag::*
         },
-        value::Value, env::Env
+        value::{Value, OperationError}, env::Env
     }, lexer::token::TokenTag, errors::Span
 };
 
@@ -18,21 +18,32 @@ use super::evaluatable::Evaluatable;
 impl Evaluatable for UnaryNode {
     fn eval(&self, env: &mut Env) -> Result<Value, InterpreterException> {
         let left = self.left.eval(env)?;
+        let operand = left.type_name();
 
         let val =match self.op.tag {
             TokenTag::Minus => {
                -left
             },
+            TokenTag::Tilde => match left {
+                Value::Int(n) => Ok(Value::Int(!n)),
+                _ => Err(OperationError::Incompatible)
+            },
+            TokenTag::Bang | TokenTag::Not => {
+                Ok(Value::Boolean(!left.to_boolean()))
+            },
             _ => unreachable!()
         };
 
         match val {
-            Err(_) => Err(Fatal(
+            Err(err) => Err(Fatal(
                 RuntimeError {
                     span: Span::from(self.clone()),
-                    tag: IncompatibleOperand {
-                        expr: self.clone(),
-                        op: self.op.clone()
+                    tag: match err {
+                        OperationError::Incompatible => IncompatibleOperand {
+                            op: self.op.clone(),
+                            operand: operand.into()
+                        },
+                        OperationError::Overflow => IntegerOverflow
                     }
                 }
             )),
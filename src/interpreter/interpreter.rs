@@ -1,46 +1,148 @@
-use std::process;
+use std::{rc::Rc, path::{Path, PathBuf}};
 use crate::{
     parser::ast::statement::Statement,
-    errors::DescribableError
+    errors::Span
 };
 use super::{
     statement::Executable,
+    expression::call_value,
     env::Env,
+    value::{Value, NativeFunction},
+    builtins::global_env,
+    arity::check_arity,
     runtime_exception::{
         InterpreterException,
         RuntimeError,
-        RuntimeErrorTag::*
+        RuntimeErrorTag::{FunctionNotDefined, NativeError}
     }
 };
 
 pub struct Interpreter {
-    statements: Vec<Statement>
+    global_env: Env
 }
 
 impl Interpreter {
-    pub fn new(statements: Vec<Statement>) -> Interpreter {
-        Interpreter { statements: statements }
-    }
-
-    pub fn interpret(&self) -> () {
-        let mut global_env = Box::new(Env::new());
-
-        for stmt in &self.statements {
-            if let Err(error) = stmt.run(global_env.as_mut()) {
-                match error {
-                    InterpreterException::Fatal(fatal) => {
-                        fatal.print();
-                        process::exit(1)
-                    },
-                    InterpreterException::Return(value) => {
-                        RuntimeError {
-                            span: value.span,
-                            tag: ReturnOutOfFunction
-                        }.print();
-                        process::exit(1)
-                    }
-                };
-            };
+    pub fn new() -> Interpreter {
+        Interpreter { global_env: global_env(Env::new()) }
+    }
+
+    /// Execute statements one by one in the global environment.
+    /// Expression statements, blocks and conditions yield their values,
+    /// the value of the last statement is returned.
+    ///
+    /// # Errors
+    /// Execution stops at the first error, which is returned to the caller.
+    /// Calls of the named functions with the wrong number of arguments
+    /// are reported before the execution starts.
+    pub fn interpret(&mut self, statements: &[Statement]) -> Result<Value, RuntimeError> {
+        self.interpret_each(statements, |_| ())
+    }
+
+    /// Execute statements just like `interpret`, passing the value
+    /// of every expression statement to `on_value` as soon as it's executed.
+    pub fn interpret_each<F>(
+        &mut self,
+        statements: &[Statement],
+        mut on_value: F
+    ) -> Result<Value, RuntimeError>
+    where
+        F: FnMut(&Value)
+    {
+        check_arity(statements)?;
+
+        let mut value = Value::Null;
+
+        for stmt in statements {
+            value = stmt.run(&mut self.global_env)
+                .map_err(InterpreterException::into_error)?;
+
+            if let Statement::Expr(_) = stmt {
+                on_value(&value);
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Execute statements of the script file, just like `interpret`.
+    /// Modules importing the file back are reported as circular imports.
+    pub fn interpret_file(
+        &mut self,
+        statements: &[Statement],
+        path: &Path
+    ) -> Result<Value, RuntimeError> {
+        let modules = self.global_env.modules();
+
+        modules.borrow_mut().enter(path);
+        let value = self.interpret(statements);
+        modules.borrow_mut().leave();
+
+        value
+    }
+
+    /// Call the global function with the arguments.
+    /// Errors of the call itself are reported at the `span`.
+    pub fn call_function(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        span: Span
+    ) -> Result<Value, RuntimeError> {
+        let function = self.get_global(name).ok_or_else(|| RuntimeError {
+            span: span.clone(),
+            tag: FunctionNotDefined { name: name.into() }
+        })?;
+
+        call_value(function, args, vec![], span.clone(), span)
+            .map_err(InterpreterException::into_error)
+    }
+
+    /// Get the variable, looking up the built-in functions as well
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.global_env.get(&name.into())
+    }
+
+    /// Define the global variable, or override the existing one
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        let name = name.to_string();
+
+        if self.global_env.get_local(&name).is_some() {
+            self.global_env.set(&name, value)
+        } else {
+            self.global_env.define(&name, value)
+        }.expect("global variable is checked to exist");
+    }
+
+    /// Search the imported modules in the `dirs`, after the directory
+    /// of the importing file, instead of the ones in `NOVIS_PATH`
+    pub fn set_search_path(&mut self, dirs: Vec<PathBuf>) {
+        self.global_env.modules().borrow_mut().set_search_path(dirs)
+    }
+
+    /// Define the Rust function as a global variable.
+    /// Error message returned by it is raised as `NativeError`.
+    pub fn register_fn<F>(&mut self, name: &str, arity: usize, func: F)
+    where
+        F: Fn(Vec<Value>) -> Result<Value, String> + 'static
+    {
+        let error_name = name.to_string();
+        let native = NativeFunction {
+            name: name.into(),
+            arity: arity,
+            func: Rc::new(move |args| func(args).map_err(|message| NativeError {
+                name: error_name.clone(),
+                message: message
+            }))
         };
+
+        self.set_global(name, Value::NativeFunction(native));
+    }
+}
+
+// Functions and the scopes they are defined in refer to each other,
+// so the scopes are cleared explicitly for anything to be freed
+impl Drop for Interpreter {
+    fn drop(&mut self) {
+        self.global_env.release()
     }
 }
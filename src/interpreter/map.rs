@@ -0,0 +1,148 @@
+use std::collections::{HashMap, hash_map::Entry};
+use super::{
+    value::Value,
+    runtime_exception::RuntimeErrorTag::{self, *}
+};
+
+/// Map of values.
+///
+/// Entries are kept in insertion order, keys are compared with the
+/// same semantics as `==` does, so `1` and `1.0` is the same key.
+/// Only strings, numbers and booleans can
+/// be used as keys. `NaN` is rejected, because it is not equal to
+/// anything, including itself, so it could never be found again.
+#[derive(Clone, Debug)]
+pub struct Map {
+    entries: Vec<(Value, Value)>,
+    /// Position of the entry by its key
+    index: HashMap<Key, usize>
+}
+
+/// Key of the map entry, normalized so that the keys equal by `==`
+/// are the same: floats without fractional part become integers
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    String(String),
+    Int(i64),
+    /// Bits of the float, which is never `NaN` or integral
+    Number(u64),
+    Boolean(bool)
+}
+
+impl TryFrom<&Value> for Key {
+    type Error = RuntimeErrorTag;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) if n.is_nan() => Err(InvalidMapKey {
+                kind: "NaN".into()
+            }),
+            // Bounds are powers of two, so they are exact
+            Value::Number(n) if n.fract() == 0.0
+                && *n >= i64::MIN as f64
+                && *n < -(i64::MIN as f64) => Ok(Key::Int(*n as i64)),
+            Value::Number(n) => Ok(Key::Number(n.to_bits())),
+            Value::Int(n) => Ok(Key::Int(*n)),
+            Value::String(str) => Ok(Key::String(str.as_ref().clone())),
+            Value::Boolean(boolean) => Ok(Key::Boolean(*boolean)),
+            _ => Err(InvalidMapKey {
+                kind: value.type_name().into()
+            })
+        }
+    }
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            index: HashMap::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &Vec<(Value, Value)> {
+        &self.entries
+    }
+
+    pub fn keys(&self) -> Vec<Value> {
+        self.entries
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// # Errors
+    /// Emits `KeyNotFound` if there is no such key in map.
+    pub fn get(&self, key: &Value) -> Result<Value, RuntimeErrorTag> {
+        let index = self.find(key)?;
+        Ok(self.entries[index].1.clone())
+    }
+
+    pub fn contains(&self, key: &Value) -> Result<bool, RuntimeErrorTag> {
+        Ok(self.index.contains_key(&Key::try_from(key)?))
+    }
+
+    /// Insert new entry, or override value of the existing one
+    pub fn insert(&mut self, key: Value, value: Value) -> Result<(), RuntimeErrorTag> {
+        match self.index.entry(Key::try_from(&key)?) {
+            Entry::Occupied(entry) => self.entries[*entry.get()].1 = value,
+            Entry::Vacant(entry) => {
+                entry.insert(self.entries.len());
+                self.entries.push((key, value));
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Remove entry and return its value
+    ///
+    /// # Errors
+    /// Emits `KeyNotFound` if there is no such key in map.
+    pub fn remove(&mut self, key: &Value) -> Result<Value, RuntimeErrorTag> {
+        let removed = self.find(key)?;
+        self.index.remove(&Key::try_from(key)?);
+
+        // Entries after the removed one are shifted to keep the order
+        for index in self.index.values_mut() {
+            if *index > removed {
+                *index -= 1;
+            }
+        }
+
+        Ok(self.entries.remove(removed).1)
+    }
+
+    fn find(&self, key: &Value) -> Result<usize, RuntimeErrorTag> {
+        self.index
+            .get(&Key::try_from(key)?)
+            .copied()
+            .ok_or_else(|| KeyNotFound {
+                key: key.to_repr().unwrap_or_default()
+            })
+    }
+}
+
+// Maps are equal if they have the same entries, no matter in what order
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut vec![])
+    }
+}
+
+impl Map {
+    /// Compare the maps the way `Value::equals` does
+    pub fn equals(&self, other: &Map, path: &mut Vec<(*const (), *const ())>) -> bool {
+        self.len() == other.len() && self.entries.iter().all(|(key, value)| {
+            other.get(key).is_ok_and(|other| value.equals(&other, path))
+        })
+    }
+}
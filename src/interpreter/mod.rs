@@ -1,9 +1,18 @@
 mod runtime_exception;
+mod builtins;
 mod env;
 mod expression;
 mod statement;
 mod interpreter;
 mod utils;
 mod value;
+mod map;
+mod object;
+mod pattern;
+mod module;
+mod arity;
+mod tests;
 
 pub use interpreter::Interpreter;
+pub use value::Value;
+pub use runtime_exception::RuntimeError;
@@ -0,0 +1,232 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc
+};
+use crate::{
+    errors::{DescribableError, Span},
+    lexer::Lexer,
+    parser::Parser,
+    interpreter::{
+        runtime_exception::{
+            InterpreterException::{self, *},
+            RuntimeError,
+            RuntimeErrorTag::{self, *}
+        },
+        builtins::global_env,
+        arity::check_arity,
+        statement::Executable,
+        value::Value,
+        env::Env
+    }
+};
+
+/// Extension of the module files
+const EXTENSION: &str = "novis";
+
+/// Environment variable with the directories modules are searched in,
+/// after the directory of the importing file, unless the host sets them
+const SEARCH_PATH: &str = "NOVIS_PATH";
+
+/// Module imported by the `import` statement
+#[derive(Debug)]
+pub struct Module {
+    pub name: String,
+    /// Global environment the module is executed in,
+    /// its top-level definitions are accessed as properties
+    pub env: Env
+}
+
+impl Module {
+    /// # Errors
+    /// Emits `UnknownProperty` if there is no such top-level definition.
+    pub fn get(&self, name: &str) -> Result<Value, RuntimeErrorTag> {
+        self.env
+            .get_local(&name.into())
+            .ok_or_else(|| UnknownProperty {
+                class: self.name.clone(),
+                name: name.into()
+            })
+    }
+}
+
+/// Modules of the program, shared by all of its environments
+#[derive(Debug, Default)]
+pub struct Modules {
+    /// Modules already executed, by their canonical path
+    loaded: HashMap<PathBuf, Rc<Module>>,
+    /// Modules being executed along with the import which started it,
+    /// the innermost is the last. The script file run by the host
+    /// comes first and has no import.
+    loading: Vec<(PathBuf, Option<Span>)>,
+    /// Directories set by the host, replacing the ones in `NOVIS_PATH`
+    search_path: Option<Vec<PathBuf>>
+}
+
+impl Modules {
+    /// Register the script file being executed by the host,
+    /// so that importing it back is reported as a circular import
+    pub fn enter(&mut self, path: &Path) {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.into());
+        self.loading.push((canonical, None));
+    }
+
+    /// Finish the execution of the script file registered by `enter`
+    pub fn leave(&mut self) {
+        self.loading.pop();
+    }
+
+    /// Search the modules in the `dirs` instead of the ones in `NOVIS_PATH`
+    pub fn set_search_path(&mut self, dirs: Vec<PathBuf>) {
+        self.search_path = Some(dirs);
+    }
+
+    /// Directories modules are searched in after the directory
+    /// of the importing file
+    fn search_path(&self) -> Vec<PathBuf> {
+        match &self.search_path {
+            Some(dirs) => dirs.clone(),
+            None => env::var_os(SEARCH_PATH)
+                .map(|dirs| env::split_paths(&dirs).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Forget the modules already executed, returning them
+    pub fn unload(&mut self) -> Vec<Rc<Module>> {
+        self.loaded.drain().map(|(_, module)| module).collect()
+    }
+}
+
+/// Import the module, executing it first if it's not imported yet.
+///
+/// # Arguments
+/// * `env` - Environment of the importing module
+/// * `path` - Path of the module file, relative to the importing file
+///   or to one of the directories in the search path
+/// * `span` - Span of the import statement
+pub fn import(
+    env: &Env,
+    path: &str,
+    span: &Span
+) -> Result<Rc<Module>, InterpreterException> {
+    let modules = env.modules();
+    let search_path = modules.borrow().search_path();
+
+    let (path, canonical) = resolve(path, &span.start.info.fname, search_path)
+        .ok_or_else(|| error(span, ModuleNotFound { path: path.into() }))?;
+
+    if let Some(module) = modules.borrow().loaded.get(&canonical) {
+        return Ok(module.clone());
+    }
+
+    let cycle = modules
+        .borrow()
+        .loading
+        .iter()
+        .position(|(loading, _)| *loading == canonical);
+
+    if let Some(start) = cycle {
+        // Import of the next module in the cycle is made
+        // by the one which is imported again
+        let next = modules.borrow().loading.get(start + 1).and_then(|(_, span)| span.clone());
+
+        return Err(error(span, CircularImport {
+            path: path.display().to_string(),
+            importer: span.start.info.fname.clone(),
+            cycle: next
+        }));
+    }
+
+    modules.borrow_mut().loading.push((canonical.clone(), Some(span.clone())));
+    let module = execute(env, &path, span);
+    modules.borrow_mut().loading.pop();
+
+    let module = Rc::new(module?);
+    modules.borrow_mut().loaded.insert(canonical, module.clone());
+
+    Ok(module)
+}
+
+/// Name the module is imported as, which is the file name without extension
+pub fn module_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into())
+        .unwrap_or_else(|| path.into())
+}
+
+/// Path of the module file by the name it's imported with
+pub fn module_path(name: &str) -> String {
+    format!("{name}.{EXTENSION}")
+}
+
+/// Find the module file, returning its path along with the canonical one
+fn resolve(path: &str, importer: &str, search_path: Vec<PathBuf>) -> Option<(PathBuf, PathBuf)> {
+    let importer_dir = Path::new(importer)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    std::iter::once(importer_dir)
+        .chain(search_path)
+        .map(|dir| dir.join(path))
+        .find_map(|path| {
+            let canonical = fs::canonicalize(&path).ok()?;
+            canonical.is_file().then_some((path, canonical))
+        })
+}
+
+/// Lex, parse, check and execute the module in the new global environment
+fn execute(env: &Env, path: &Path, span: &Span) -> Result<Module, InterpreterException> {
+    let fname = path.display().to_string();
+    let invalid_module = |errors: Vec<String>| error(span, InvalidModule {
+        path: fname.clone(),
+        errors: errors
+    });
+
+    let src = fs::read_to_string(path)
+        .map_err(|error| invalid_module(vec![format!("failed to read the file ({error})")]))?;
+
+    let tokens = Lexer::new(src, fname.clone())
+        .lex()
+        .map_err(|errors| invalid_module(describe(&errors)))?;
+
+    let statements = Parser::new(tokens)
+        .parse()
+        .map_err(|errors| invalid_module(describe(&errors)))?;
+
+    check_arity(&statements).map_err(|error| invalid_module(describe(&[error])))?;
+
+    let mut module_env = global_env(env.fork());
+
+    for stmt in &statements {
+        match stmt.run(&mut module_env) {
+            Ok(_) => (),
+            // Errors and thrown values may be handled by the importer
+            Err(exception @ (Fatal(_) | Throw(_))) => return Err(exception),
+            Err(exception) => return Err(Fatal(exception.into_error()))
+        }
+    }
+
+    Ok(Module {
+        name: module_name(&fname),
+        env: module_env
+    })
+}
+
+fn describe(errors: &[impl DescribableError]) -> Vec<String> {
+    errors
+        .iter()
+        .map(|e| format!("{}: {}\n{}", e.kind(), e.message(), e.snippet()))
+        .collect()
+}
+
+fn error(span: &Span, tag: RuntimeErrorTag) -> InterpreterException {
+    Fatal(RuntimeError {
+        span: span.clone(),
+        tag: tag
+    })
+}
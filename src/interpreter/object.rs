@@ -0,0 +1,90 @@
+use std::{rc::Rc, cell::RefCell};
+use crate::parser::ast::statement::Let;
+use super::{
+    env::Env,
+    value::Value,
+    runtime_exception::RuntimeErrorTag::{self, *}
+};
+
+/// User-defined type, created by the `class` declaration
+#[derive(Debug)]
+pub struct Class {
+    pub name: String,
+    pub fields: Rc<Vec<Let>>,
+    /// Methods are kept unbound, `self` is defined on the property access
+    pub methods: Vec<(String, Value)>,
+    /// Environment the class is declared in
+    pub closure: Env
+}
+
+impl Class {
+    pub fn method(&self, name: &str) -> Option<&Value> {
+        self.methods
+            .iter()
+            .find(|(method, _)| method == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Instance of the user-defined type
+#[derive(Debug)]
+pub struct Instance {
+    pub class: Rc<Class>,
+    /// Fields in the order they are declared
+    pub fields: Vec<(String, Value)>
+}
+
+impl Instance {
+    /// Get property of the instance.
+    /// Fields are looked up first, then methods, which are bound to the
+    /// `instance` they are accessed on.
+    ///
+    /// # Errors
+    /// Emits `UnknownProperty` if there is no field or method with such name.
+    pub fn get(instance: &Rc<RefCell<Instance>>, name: &str) -> Result<Value, RuntimeErrorTag> {
+        let object = instance.borrow();
+
+        if let Some((_, value)) = object.fields.iter().find(|(field, _)| field == name) {
+            return Ok(value.clone());
+        }
+
+        match object.class.method(name) {
+            Some(Value::Function { params, name, body, closure }) => {
+                let mut closure = closure.clone();
+                closure.enter();
+                closure
+                    .define(&"self".into(), Value::Instance(instance.clone()))
+                    .expect("`self` is defined twice");
+
+                Ok(Value::Function {
+                    params: params.clone(),
+                    name: name.clone(),
+                    body: body.clone(),
+                    closure: closure
+                })
+            },
+            _ => Err(object.unknown_property(name))
+        }
+    }
+
+    /// Set field of the instance. Only declared fields can be set.
+    ///
+    /// # Errors
+    /// Emits `UnknownProperty` if there is no field with such name.
+    pub fn set(&mut self, name: &str, value: Value) -> Result<(), RuntimeErrorTag> {
+        match self.fields.iter_mut().find(|(field, _)| field == name) {
+            Some(field) => {
+                field.1 = value;
+                Ok(())
+            },
+            None => Err(self.unknown_property(name))
+        }
+    }
+
+    fn unknown_property(&self, name: &str) -> RuntimeErrorTag {
+        UnknownProperty {
+            class: self.class.name.clone(),
+            name: name.into()
+        }
+    }
+}
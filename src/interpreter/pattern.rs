@@ -0,0 +1,165 @@
+use std::cmp::Ordering;
+use crate::{
+    parser::ast::{
+        pattern::{Pattern, Literal},
+        statement::Arm
+    },
+    interpreter::{
+        runtime_exception::{
+            InterpreterException,
+            RuntimeError
+        },
+        value::Value,
+        object::Instance,
+        env::Env,
+        expression::{Evaluatable, literal},
+        utils::check_condition
+    },
+    lexer::token::{TokenTag, Token}
+};
+
+/// Run the match arm if its pattern matches the `value` and its guard holds.
+/// Returns `None` otherwise.
+///
+/// Names bound by the pattern are defined in the `env`, so the arm
+/// should be run in its own scope.
+pub fn run_arm(
+    env: &mut Env,
+    arm: &Arm,
+    value: &Value
+) -> Result<Option<Value>, InterpreterException> {
+    if !matches(env, &arm.pattern, value)? {
+        return Ok(None);
+    }
+
+    if let Some(guard) = &arm.guard {
+        if !check_condition(env, guard)? {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(arm.body.eval(env)?))
+}
+
+/// Check if the `value` matches the `pattern`, binding the names on the way
+fn matches(
+    env: &mut Env,
+    pattern: &Pattern,
+    value: &Value
+) -> Result<bool, InterpreterException> {
+    match pattern {
+        Pattern::Wildcard(_) => Ok(true),
+        Pattern::Literal(literal) => Ok(*value == literal_value(literal)?),
+        Pattern::Range { start, op, end } => {
+            let above_start = matches!(
+                value.compare(&literal_value(start)?),
+                Some(Ordering::Greater | Ordering::Equal)
+            );
+
+            let below_end = match value.compare(&literal_value(end)?) {
+                Some(Ordering::Less) => true,
+                Some(Ordering::Equal) => op.tag == TokenTag::DotDotEqual,
+                _ => false
+            };
+
+            Ok(above_start && below_end)
+        },
+        Pattern::Binding(name) => {
+            bind(env, name, value.clone())?;
+            Ok(true)
+        },
+        Pattern::List { lsquare: _, items, rsquare: _ } => {
+            let list = match value {
+                Value::List(list) => list.borrow().clone(),
+                _ => return Ok(false)
+            };
+
+            if list.len() != items.len() {
+                return Ok(false);
+            }
+
+            for (pattern, item) in items.iter().zip(list.iter()) {
+                if !matches(env, pattern, item)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        },
+        Pattern::Record { class, lcurly: _, fields, rcurly: _ } => {
+            match (class, value) {
+                (None, Value::Map(_) | Value::Instance(_)) => (),
+                (Some(class), Value::Instance(instance))
+                    if class.get_lexeme() == instance.borrow().class.name => (),
+                _ => return Ok(false)
+            };
+
+            for field in fields {
+                let name = match &field.key.tag {
+                    TokenTag::Identifier(name) | TokenTag::String(name) => name,
+                    _ => unreachable!()
+                };
+
+                let item = match value {
+                    Value::Map(map) => map
+                        .borrow()
+                        .get(&Value::String(Box::new(name.clone())))
+                        .ok(),
+                    Value::Instance(instance) => match Instance::get(instance, name) {
+                        Ok(item) => Some(item),
+                        // Field must exist, if the class is named explicitly
+                        Err(tag) if class.is_some() => {
+                            return Err(InterpreterException::Fatal(RuntimeError {
+                                span: field.key.clone().into(),
+                                tag: tag
+                            }));
+                        },
+                        Err(_) => None
+                    },
+                    _ => unreachable!()
+                };
+
+                let Some(item) = item else {
+                    return Ok(false);
+                };
+
+                let matched = match &field.pattern {
+                    Some(pattern) => matches(env, pattern, &item)?,
+                    None => {
+                        bind(env, &field.key, item)?;
+                        true
+                    }
+                };
+
+                if !matched {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+    }
+}
+
+fn literal_value(pattern: &Literal) -> Result<Value, InterpreterException> {
+    let value = literal(&pattern.token)?;
+
+    Ok(match (&pattern.minus, value) {
+        (Some(_), Value::Int(n)) => Value::Int(n.wrapping_neg()),
+        (Some(_), Value::Number(n)) => Value::Number(-n),
+        (_, value) => value
+    })
+}
+
+fn bind(env: &mut Env, name: &Token, value: Value) -> Result<(), InterpreterException> {
+    let TokenTag::Identifier(id) = &name.tag else {
+        unreachable!()
+    };
+
+    env.define(id, value).map_err(|tag| {
+        InterpreterException::Fatal(RuntimeError {
+            span: name.clone().into(),
+            tag: tag
+        })
+    })
+}
@@ -2,18 +2,52 @@ use crate::{
     errors::{
         DescribableError, Span
     },
-    lexer::token::Token, parser::ast::expression::{
-        BinaryNode,
-        UnaryNode
-    }
+    lexer::token::{Token, TokenTag}
 };
 
-use super::value::Value;
+use super::{
+    value::Value,
+    map::Map
+};
 
 #[derive(Debug)]
 pub enum InterpreterException {
     Fatal(RuntimeError),
-    Return(ReturnValue)
+    Return(ReturnValue),
+    /// Value thrown by the `throw` statement
+    Throw(ReturnValue),
+    Break(Span),
+    Continue(Span)
+}
+
+impl InterpreterException {
+    /// Turn the exception into error.
+    /// Used when control flow escapes the construct it belongs to,
+    /// for example `break` out of the function body,
+    /// or the thrown value is not caught.
+    pub fn into_error(self) -> RuntimeError {
+        match self {
+            Self::Fatal(error) => error,
+            Self::Throw(value) => RuntimeError {
+                span: value.span,
+                tag: RuntimeErrorTag::UncaughtThrow {
+                    value: value.val.to_repr().unwrap_or_default()
+                }
+            },
+            Self::Return(value) => RuntimeError {
+                span: value.span,
+                tag: RuntimeErrorTag::ReturnOutOfFunction
+            },
+            Self::Break(span) => RuntimeError {
+                span: span,
+                tag: RuntimeErrorTag::BreakOutOfLoop
+            },
+            Self::Continue(span) => RuntimeError {
+                span: span,
+                tag: RuntimeErrorTag::ContinueOutOfLoop
+            },
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -28,18 +62,45 @@ pub struct RuntimeError {
     pub tag: RuntimeErrorTag
 }
 
+impl RuntimeError {
+    /// Map the error is seen as by the `catch` clause,
+    /// with the `kind`, `message` and `line` of the error
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        let entries = [
+            ("kind", Value::String(Box::new(self.tag.kind().into()))),
+            ("message", Value::String(Box::new(self.tag.to_human_readable()))),
+            ("line", Value::Int(self.span.start.info.line as i64)),
+        ];
+
+        for (key, value) in entries {
+            map.insert(Value::String(Box::new(key.into())), value)
+                .expect("string is a valid map key");
+        }
+
+        Value::map(map)
+    }
+}
+
 #[derive(Debug)]
 pub enum RuntimeErrorTag {
     IncompatibleOperands {
-        expr: BinaryNode,
         op: Token,
+        left: String,
+        right: String
     },
     IncompatibleOperand {
-        expr: UnaryNode,
-        op: Token
+        op: Token,
+        operand: String
     },
     DivisionByZero,
+    IntegerOverflow,
+    NegativeShiftCount {
+        count: i64
+    },
     ReturnOutOfFunction,
+    BreakOutOfLoop,
+    ContinueOutOfLoop,
     ObjectIsNotCallable,
     ConversionError {
         from: String,
@@ -53,6 +114,74 @@ pub enum RuntimeErrorTag {
     },
     NameRedefinition {
         name: String
+    },
+    InvalidRepeatCount {
+        value: String
+    },
+    ObjectIsNotIndexable,
+    InvalidIndex {
+        value: String
+    },
+    IndexOutOfRange {
+        index: String
+    },
+    KeyNotFound {
+        key: String
+    },
+    InvalidMapKey {
+        kind: String
+    },
+    ObjectHasNoProperties {
+        type_name: String
+    },
+    UnknownProperty {
+        class: String,
+        name: String
+    },
+    ArityMismatch {
+        expected: usize,
+        got: usize,
+        /// Where the called function is defined, unless it's a built-in one
+        definition: Option<Span>
+    },
+    InvalidArgument {
+        message: String
+    },
+    UnknownArgument {
+        name: String
+    },
+    DuplicateArgument {
+        name: String
+    },
+    MissingArgument {
+        name: String
+    },
+    NoMatchingArm {
+        value: String
+    },
+    UncaughtThrow {
+        value: String
+    },
+    ModuleNotFound {
+        path: String
+    },
+    InvalidModule {
+        path: String,
+        errors: Vec<String>
+    },
+    CircularImport {
+        path: String,
+        importer: String,
+        /// Import the cycle starts with, if it's not the import of itself
+        cycle: Option<Span>
+    },
+    ReadOnlyModule {
+        name: String
+    },
+    /// Error returned by the function registered by the host
+    NativeError {
+        name: String,
+        message: String
     }
 }
 
@@ -62,12 +191,24 @@ impl RuntimeErrorTag {
             Self::DivisionByZero => {
                 format!("Division by zero")
             },
+            Self::IntegerOverflow => {
+                format!("Integer overflow")
+            },
+            Self::NegativeShiftCount { count } => {
+                format!("Shift count must be non-negative, got `{}`", count)
+            },
             Self::ObjectIsNotCallable => {
                 format!("Object is not callable")
             }
             Self::ReturnOutOfFunction => {
                 format!("Cannot return value outside of the function")
             },
+            Self::BreakOutOfLoop => {
+                format!("Cannot break outside of the loop")
+            },
+            Self::ContinueOutOfLoop => {
+                format!("Cannot continue outside of the loop")
+            },
             Self::ConversionError {
                 from,
                 to
@@ -80,15 +221,143 @@ impl RuntimeErrorTag {
             Self::NameNotDefined { name } => {
                 format!("Name `{}` not defined", name)
             },
-            Self::IncompatibleOperands { expr: _, op } => {
-                format!("Cannot perform `{:?}` between operands", op.tag)
+            Self::IncompatibleOperands { op, left, right } => match op.tag {
+                TokenTag::Ampersand
+                | TokenTag::Pipe
+                | TokenTag::Tilde
+                | TokenTag::LessLess
+                | TokenTag::GreaterGreater => format!(
+                    "Bitwise `{}` requires integer operands, got `{}` and `{}`",
+                    op.get_lexeme(), left, right
+                ),
+                _ => format!(
+                    "Cannot perform `{}` between `{}` and `{}`",
+                    op.get_lexeme(), left, right
+                )
             },
-            Self::IncompatibleOperand { expr: _, op } => {
-                format!("Cannot perform `{:?}` to the operand", op.tag)
+            Self::IncompatibleOperand { op, operand } => match op.tag {
+                TokenTag::Tilde => format!(
+                    "Bitwise `~` requires integer operand, got `{}`", operand
+                ),
+                _ => format!("Cannot apply `{}` to `{}`", op.get_lexeme(), operand)
             },
             Self::NameRedefinition { name } => {
                 format!("Name `{}` is already defined", name)
             },
+            Self::InvalidRepeatCount { value } => {
+                format!("Repeat count must be a non-negative integer, got `{}`", value)
+            },
+            Self::ObjectIsNotIndexable => {
+                format!("Object is not indexable")
+            },
+            Self::InvalidIndex { value } => {
+                format!("Index must be an integer, got `{}`", value)
+            },
+            Self::IndexOutOfRange { index } => {
+                format!("Index `{}` is out of range", index)
+            },
+            Self::KeyNotFound { key } => {
+                format!("Key `{}` is not found in the map", key)
+            },
+            Self::InvalidMapKey { kind } => {
+                format!("`{}` can't be used as a map key", kind)
+            },
+            Self::ObjectHasNoProperties { type_name } => {
+                format!("Value of type `{}` has no properties", type_name)
+            },
+            Self::UnknownProperty { class, name } => {
+                format!("`{}` has no property `{}`", class, name)
+            },
+            Self::ArityMismatch { expected, got, definition: _ } => {
+                format!("Expected {} arguments, got {}", expected, got)
+            },
+            Self::InvalidArgument { message } => {
+                format!("Invalid argument: {}", message)
+            },
+            Self::UnknownArgument { name } => {
+                format!("Function has no parameter `{}`", name)
+            },
+            Self::DuplicateArgument { name } => {
+                format!("Argument `{}` is passed more than once", name)
+            },
+            Self::MissingArgument { name } => {
+                format!("Argument `{}` is not passed", name)
+            },
+            Self::NoMatchingArm { value } => {
+                format!("No match arm for value `{}`", value)
+            },
+            Self::UncaughtThrow { value } => {
+                format!("Uncaught exception `{}`", value)
+            },
+            Self::ModuleNotFound { path } => {
+                format!("Module `{}` is not found", path)
+            },
+            Self::InvalidModule { path, errors } => {
+                format!("Module `{}` has errors\n{}", path, errors.join("\n"))
+            },
+            Self::CircularImport { path, importer, cycle: _ } => format!(
+                "Circular import of `{}` in `{}`, it is still being executed",
+                path, importer
+            ),
+            Self::ReadOnlyModule { name } => {
+                format!("Properties of the module `{}` can't be assigned", name)
+            },
+            Self::NativeError { name, message } => {
+                format!("`{}` failed: {}", name, message)
+            },
+        }
+    }
+
+    /// Additional location related to the error
+    pub fn note(&self) -> Option<String> {
+        match self {
+            Self::CircularImport { cycle: Some(span), .. } => Some(format!(
+                "note: `{}` starts the cycle here\n{}",
+                span.start.info.fname, span
+            )),
+            Self::ArityMismatch { definition: Some(span), .. } => {
+                Some(format!("note: function is defined here\n{}", span))
+            },
+            _ => None
+        }
+    }
+
+    /// Name of the error kind, seen by the programs catching the error
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::IncompatibleOperands { .. } => "IncompatibleOperands",
+            Self::IncompatibleOperand { .. } => "IncompatibleOperand",
+            Self::DivisionByZero => "DivisionByZero",
+            Self::IntegerOverflow => "IntegerOverflow",
+            Self::NegativeShiftCount { .. } => "NegativeShiftCount",
+            Self::ReturnOutOfFunction => "ReturnOutOfFunction",
+            Self::BreakOutOfLoop => "BreakOutOfLoop",
+            Self::ContinueOutOfLoop => "ContinueOutOfLoop",
+            Self::ObjectIsNotCallable => "ObjectIsNotCallable",
+            Self::ConversionError { .. } => "ConversionError",
+            Self::FunctionNotDefined { .. } => "FunctionNotDefined",
+            Self::NameNotDefined { .. } => "NameNotDefined",
+            Self::NameRedefinition { .. } => "NameRedefinition",
+            Self::InvalidRepeatCount { .. } => "InvalidRepeatCount",
+            Self::ObjectIsNotIndexable => "ObjectIsNotIndexable",
+            Self::InvalidIndex { .. } => "InvalidIndex",
+            Self::IndexOutOfRange { .. } => "IndexOutOfRange",
+            Self::KeyNotFound { .. } => "KeyNotFound",
+            Self::InvalidMapKey { .. } => "InvalidMapKey",
+            Self::ObjectHasNoProperties { .. } => "ObjectHasNoProperties",
+            Self::UnknownProperty { .. } => "UnknownProperty",
+            Self::ArityMismatch { .. } => "ArityMismatch",
+            Self::InvalidArgument { .. } => "InvalidArgument",
+            Self::UnknownArgument { .. } => "UnknownArgument",
+            Self::DuplicateArgument { .. } => "DuplicateArgument",
+            Self::MissingArgument { .. } => "MissingArgument",
+            Self::NoMatchingArm { .. } => "NoMatchingArm",
+            Self::UncaughtThrow { .. } => "UncaughtThrow",
+            Self::ModuleNotFound { .. } => "ModuleNotFound",
+            Self::InvalidModule { .. } => "InvalidModule",
+            Self::CircularImport { .. } => "CircularImport",
+            Self::ReadOnlyModule { .. } => "ReadOnlyModule",
+            Self::NativeError { .. } => "NativeError",
         }
     }
 }
@@ -99,7 +368,10 @@ impl DescribableError for RuntimeError {
     }
 
     fn snippet(&self) -> String {
-        self.span.to_string()
+        match self.tag.note() {
+            Some(note) => format!("{}\n{}", self.span, note),
+            None => self.span.to_string()
+        }
     }
 
     fn message(&self) -> String {
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use crate::{
     interpreter::{
         runtime_exception::{
@@ -7,23 +9,49 @@ use crate::{
             RuntimeErrorTag::*
         },
         value::Value,
+        object::Class as ClassValue,
         env::Env,
-        expression::Evaluatable,
-        utils::check_condition
+        expression::{
+            Evaluatable,
+            binary_op
+        },
+        pattern::run_arm,
+        module::{self, module_name, module_path},
+        utils::{
+            check_condition,
+            run_loop_body,
+            index_error,
+            property_error
+        }
+    },
+    parser::ast::expression::{
+        ExpressionNode,
+        PrimaryNode
     },
     parser::ast::statement::{
         Print,
         Return,
+        Break,
+        Continue,
+        Throw,
+        Try,
+        Catch,
+        Import,
         Group,
         Let,
         Loop,
+        Repeat,
+        RepeatUntil,
         Cond,
+        Match,
         Func,
         Assignment,
+        Class,
         Statement,
         ExprStatment
     },
-    lexer::token::TokenTag
+    lexer::token::TokenTag,
+    errors::Span
 };
 
 use super::Executable;
@@ -34,12 +62,21 @@ impl Executable for Statement {
         match self {
             Statement::Group(group) => group.run(env),
             Statement::Retrun(ret) => ret.run(env),
+            Statement::Break(r#break) => r#break.run(env),
+            Statement::Continue(r#continue) => r#continue.run(env),
+            Statement::Throw(throw) => throw.run(env),
+            Statement::Try(r#try) => r#try.run(env),
             Statement::Print(print) => print.run(env),
             Statement::Let(r#let) => r#let.run(env),
             Statement::Func(func) => func.run(env),
             Statement::Cond(cond) => cond.run(env),
+            Statement::Match(r#match) => r#match.run(env),
             Statement::Loop(r#loop) => r#loop.run(env),
+            Statement::Repeat(repeat) => repeat.run(env),
+            Statement::RepeatUntil(repeat) => repeat.run(env),
             Statement::Assign(assign) => assign.run(env),
+            Statement::Class(class) => class.run(env),
+            Statement::Import(import) => import.run(env),
             Statement::Expr(expr) => expr.run(env),
         }
     }
@@ -53,8 +90,9 @@ impl Executable for Func {
                     &id,
                     Value::Function {
                         params: self.params.clone(),
-                        name: self.name.clone(),
-                        body: self.body.clone()
+                        name: Some(self.name.clone()),
+                        body: self.body.clone(),
+                        closure: env.clone()
                     }
                 );
 
@@ -73,42 +111,151 @@ impl Executable for Func {
     }
 }
 
-impl Executable for ExprStatment {
+impl Executable for Class {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
-        match self.expr.eval(env) {
-            Ok(_) => Ok(Value::Null),
-            Err(error) => Err(error),
+        let mut names: Vec<String> = vec![];
+        let mut methods = vec![];
+
+        let members = self.fields
+            .iter()
+            .map(|field| &field.name)
+            .chain(self.methods.iter().map(|method| &method.name));
+
+        // Fields and methods share the same namespace
+        for member in members {
+            let name = member.get_lexeme();
+
+            if names.contains(&name) {
+                return Err(InterpreterException::Fatal(RuntimeError {
+                    span: member.clone().into(),
+                    tag: NameRedefinition {
+                        name: name
+                    }
+                }));
+            }
+
+            names.push(name);
         }
+
+        for method in &self.methods {
+            methods.push((method.name.get_lexeme(), Value::Function {
+                params: method.params.clone(),
+                name: Some(method.name.clone()),
+                body: method.body.clone(),
+                closure: env.clone()
+            }));
+        }
+
+        let class = ClassValue {
+            name: self.name.get_lexeme(),
+            fields: self.fields.clone(),
+            methods: methods,
+            closure: env.clone()
+        };
+
+        env.define(&self.name.get_lexeme(), Value::Class(Rc::new(class)))
+            .map_err(|tag| InterpreterException::Fatal(RuntimeError {
+                span: self.name.clone().into(),
+                tag: tag
+            }))?;
+
+        Ok(Value::Null)
+    }
+}
+
+impl Executable for ExprStatment {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        self.expr.eval(env)
     }
 }
 
 impl Executable for Assignment {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
-        match self.name.tag.clone() {
-            TokenTag::Identifier(id) => {
-                let lval = env.get(&id);
-                if lval.is_some() {
-                    let rval = self.expr.eval(env)?;
+        match self.target.get_node() {
+            ExpressionNode::Primary(PrimaryNode::Identifier(name)) => {
+                let id = name.get_lexeme();
 
-                    match self.operator.tag {
-                        TokenTag::ArrowLeft => env.set(&id, rval).unwrap(),
-                        _ => unreachable!()
-                    }
+                if let Some(lval) = env.get(&id) {
+                    let val = self.value(env, lval)?;
+                    env.set(&id, val).unwrap();
                 } else {
                     return Err(InterpreterException::Fatal(RuntimeError {
-                        span: self.name.clone().into(),
+                        span: name.clone().into(),
                         tag: NameNotDefined {
                             name: id.clone()
                         }
                     }));
                 }
-            }
+            },
+            ExpressionNode::Primary(PrimaryNode::Index {
+                object,
+                index,
+                rsquare: _
+            }) => {
+                let collection = object.eval(env)?;
+                let key = index.eval(env)?;
+
+                let lval = match self.operator.tag {
+                    TokenTag::ArrowLeft => Value::Null,
+                    _ => collection
+                        .get_item(&key)
+                        .map_err(|tag| index_error(tag, object, index))?
+                };
+
+                let val = self.value(env, lval)?;
+
+                collection
+                    .set_item(&key, val)
+                    .map_err(|tag| index_error(tag, object, index))?;
+            },
+            ExpressionNode::Primary(PrimaryNode::Property {
+                object,
+                name
+            }) => {
+                let instance = object.eval(env)?;
+                let property = name.get_lexeme();
+
+                let lval = match self.operator.tag {
+                    TokenTag::ArrowLeft => Value::Null,
+                    _ => instance
+                        .get_property(&property)
+                        .map_err(|tag| property_error(tag, object, name))?
+                };
+
+                let val = self.value(env, lval)?;
+
+                instance
+                    .set_property(&property, val)
+                    .map_err(|tag| property_error(tag, object, name))?;
+            },
             _ => unreachable!()
         };
         Ok(Value::Null)
     }
 }
 
+impl Assignment {
+    /// Evaluate the value to assign, applying the operator
+    /// of compound assignment to the current value `lval`
+    fn value(&self, env: &mut Env, lval: Value) -> Result<Value, InterpreterException> {
+        let rval = self.expr.eval(env)?;
+
+        match self.operator.tag {
+            TokenTag::ArrowLeft => Ok(rval),
+            // Compound assignment, e.g. `a += 1`
+            _ => binary_op(&self.operator, lval, rval).map_err(|tag| {
+                InterpreterException::Fatal(RuntimeError {
+                    span: Span {
+                        start: Span::from(self.target.as_ref().clone()).start,
+                        end: Span::from(self.expr.as_ref().clone()).end
+                    },
+                    tag: tag
+                })
+            })
+        }
+    }
+}
+
 impl Executable for Let {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
         match self.name.tag.clone() {
@@ -134,22 +281,98 @@ impl Executable for Let {
 impl Executable for Loop {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
         while check_condition(env, &self.condition)? {
-            self.body.run(env)?;
+            if !run_loop_body(env, &self.body)? {
+                break;
+            }
+        }
+
+        Ok(Value::Null)
+    }
+}
+
+impl Executable for Repeat {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        // Count is evaluated only once, before the first iteration
+        let count = match self.count.eval(env)? {
+            Value::Int(n) if n >= 0 => n as u64,
+            value => return Err(InterpreterException::Fatal(RuntimeError {
+                span: Span::from(self.count.as_ref().clone()),
+                tag: InvalidRepeatCount {
+                    value: value.to_string()?
+                }
+            }))
+        };
+
+        for _ in 0..count {
+            if !run_loop_body(env, &self.body)? {
+                break;
+            }
+        }
+
+        Ok(Value::Null)
+    }
+}
+
+impl Executable for RepeatUntil {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        loop {
+            // Condition sees the variables defined in the body,
+            // so it's checked before the body scope is left
+            let local = env.enter();
+
+            let done = match self.body.run_stmts(local) {
+                Err(InterpreterException::Break(_)) => Ok(true),
+                Err(InterpreterException::Continue(_)) | Ok(_) => {
+                    check_condition(local, &self.condition)
+                },
+                Err(exception) => Err(exception)
+            };
+
+            local.leave();
+
+            if done? {
+                break;
+            }
         }
 
         Ok(Value::Null)
     }
 }
 
+/// Value of the condition is the value of the chosen block
 impl Executable for Cond {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
-        if check_condition(env, &self.condition).unwrap() {
-            self.if_block.run(env)?;
+        if check_condition(env, &self.condition)? {
+            self.if_block.run(env)
         } else if let Some(else_block) = &self.else_block {
-            else_block.run(env)?;
+            else_block.run(env)
+        } else {
+            Ok(Value::Null)
         }
+    }
+}
 
-        Ok(Value::Null)
+impl Executable for Match {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        let value = self.scrutinee.eval(env)?;
+
+        for arm in &self.arms {
+            // Names bound by the pattern are visible in the guard and the body only
+            let local = env.enter();
+            let result = run_arm(local, arm, &value);
+            local.leave();
+
+            if let Some(result) = result.transpose() {
+                return result;
+            }
+        }
+
+        Err(InterpreterException::Fatal(RuntimeError {
+            span: Span::from(self.scrutinee.as_ref().clone()),
+            tag: NoMatchingArm {
+                value: value.to_repr()?
+            }
+        }))
     }
 }
 
@@ -173,24 +396,134 @@ impl Executable for Return {
     }
 }
 
+/// Imported module is defined as a variable named after it,
+/// importing the same module again is allowed
+impl Executable for Import {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        let (name, path) = match &self.module.tag {
+            TokenTag::Identifier(name) => (name.clone(), module_path(name)),
+            TokenTag::String(path) => (module_name(path), path.clone()),
+            _ => unreachable!()
+        };
+
+        let span = Span {
+            start: self.keyword.clone(),
+            end: self.module.clone()
+        };
+
+        let module = Value::Module(module::import(env, &path, &span)?);
+
+        if env.get_local(&name) == Some(module.clone()) {
+            return Ok(Value::Null);
+        }
+
+        env.define(&name, module).map_err(|tag| {
+            InterpreterException::Fatal(RuntimeError {
+                span: self.module.clone().into(),
+                tag: tag
+            })
+        })?;
+
+        Ok(Value::Null)
+    }
+}
+
+impl Executable for Throw {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        Err(InterpreterException::Throw(ReturnValue {
+            val: self.expr.eval(env)?,
+            span: Span {
+                start: self.keyword.clone(),
+                end: Span::from(self.expr.as_ref().clone()).end
+            }
+        }))
+    }
+}
+
+/// Value of the try statement is the value of its body,
+/// or of the `catch` clause if the body fails
+impl Executable for Try {
+    fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        let result = match (self.body.run(env), &self.catch) {
+            (Err(exception), Some(catch)) => catch.handle(env, exception),
+            (result, _) => result
+        };
+
+        // Error or control flow out of `finally` overrides the result
+        if let Some(finally) = &self.finally {
+            finally.run(env)?;
+        }
+
+        result
+    }
+}
+
+impl Catch {
+    /// Run the clause if the `exception` can be caught, which are
+    /// errors and thrown values. Errors are caught as maps with the `kind`,
+    /// `message` and `line` of the error.
+    fn handle(
+        &self,
+        env: &mut Env,
+        exception: InterpreterException
+    ) -> Result<Value, InterpreterException> {
+        let value = match exception {
+            InterpreterException::Fatal(error) => error.to_value(),
+            InterpreterException::Throw(value) => value.val,
+            exception => return Err(exception)
+        };
+
+        let TokenTag::Identifier(name) = &self.name.tag else {
+            unreachable!()
+        };
+
+        let local = env.enter();
+        local.define(name, value).expect("caught value is defined twice");
+
+        let result = self.body.run(local);
+        local.leave();
+
+        result
+    }
+}
+
+impl Executable for Break {
+    fn run(&self, _env: &mut Env) -> Result<Value, InterpreterException> {
+        Err(InterpreterException::Break(self.keyword.clone().into()))
+    }
+}
+
+impl Executable for Continue {
+    fn run(&self, _env: &mut Env) -> Result<Value, InterpreterException> {
+        Err(InterpreterException::Continue(self.keyword.clone().into()))
+    }
+}
+
+/// Value of the group is the value of its last statement
 impl Executable for Group {
     fn run(&self, env: &mut Env) -> Result<Value, InterpreterException> {
         let new_env = env.enter();
+        let result = self.run_stmts(new_env);
+
+        new_env.leave();
+        result
+    }
+}
+
+impl Group {
+    /// Run the statements in the current scope of the `env`,
+    /// returning the value of the last one
+    fn run_stmts(&self, env: &mut Env) -> Result<Value, InterpreterException> {
+        let mut result = Ok(Value::Null);
 
         for item in &self.stmts {
-            match item.run(new_env) {
-                Ok(return_value) => {
-                    match return_value {
-                        Value::Null => continue,
-                        _ => return Ok(return_value)
-                    }
-                },
-                Err(err) => {
-                    return Err(err)
-                }
+            result = item.run(env);
+
+            if result.is_err() {
+                break;
             }
         };
 
-        Ok(Value::Null)
+        result
     }
 }
@@ -0,0 +1,595 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::Parser,
+        interpreter::{
+            Interpreter,
+            value::Value,
+            runtime_exception::{RuntimeError, RuntimeErrorTag::{self, *}}
+        },
+        errors::DescribableError
+    };
+
+    fn eval(src: &str) -> Result<Value, RuntimeError> {
+        let tokens = Lexer::from_string(src.into()).lex().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        Interpreter::new().interpret(&statements)
+    }
+
+    fn error(src: &str) -> RuntimeErrorTag {
+        eval(src).expect_err("evaluation must fail").tag
+    }
+
+    fn parses(src: &str) -> bool {
+        let tokens = Lexer::from_string(src.into()).lex().unwrap();
+
+        Parser::new(tokens).parse().is_ok()
+    }
+
+    fn string(value: &str) -> Value {
+        Value::String(Box::new(value.into()))
+    }
+
+    fn strings(values: &[&str]) -> Value {
+        Value::list(values.iter().map(|value| string(value)).collect())
+    }
+
+    #[test]
+    fn class_fields_are_initialized_per_instance() {
+        let value = eval("
+            class Counter {
+                let count <- 0;
+                let history <- [];
+            }
+
+            let a <- Counter();
+            let b <- Counter();
+            a.count <- 5;
+            push(a.history, 1);
+            [a.count, b.count, len(b.history)];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![Value::Int(5), Value::Int(0), Value::Int(0)]));
+    }
+
+    #[test]
+    fn init_receives_constructor_arguments() {
+        let value = eval("
+            class Point {
+                let x <- 0;
+                let y <- 0;
+
+                func init(x, y) -> {
+                    self.x <- x;
+                    self.y <- y;
+                }
+
+                func norm2() -> self.x * self.x + self.y * self.y;
+            }
+
+            let p <- Point(3, 4);
+            p.norm2();
+        ");
+
+        assert_eq!(value.unwrap(), Value::Int(25));
+        assert!(matches!(error("class A { } A(1);"), ArityMismatch { .. }));
+    }
+
+    #[test]
+    fn methods_are_bound_to_their_instance() {
+        let value = eval("
+            class Cell {
+                let value <- 0;
+                func set(value) -> { self.value <- value; }
+                func get() -> self.value;
+            }
+
+            let a <- Cell();
+            let b <- Cell();
+            let set <- a.set;
+            set(7);
+            let get <- b.get;
+            b.value <- 2;
+            [a.value, get()];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![Value::Int(7), Value::Int(2)]));
+    }
+
+    #[test]
+    fn unknown_properties_are_runtime_errors() {
+        let class = "class A { let x <- 1; func f() -> 1; }";
+
+        assert!(matches!(error(&format!("{class} A().y;")), UnknownProperty { .. }));
+        assert!(matches!(error(&format!("{class} let a <- A(); a.y <- 1;")), UnknownProperty { .. }));
+        assert!(matches!(error("let n <- 1; n.x;"), ObjectHasNoProperties { .. }));
+    }
+
+    #[test]
+    fn integer_division_is_floored() {
+        let value = eval("[7 // 2, -7 // 2, 7 // -2, -7 // -2, 7 % 3, -7 % 3, 7 % -3, -7 % -3];");
+        let expected = [3, -4, -4, 3, 1, 2, -2, -1].into_iter().map(Value::Int).collect();
+
+        assert_eq!(value.unwrap(), Value::list(expected));
+        assert_eq!(eval("-7.5 // 2;").unwrap(), Value::Number(-4.0));
+        assert_eq!(eval("-7.5 % 2;").unwrap(), Value::Number(0.5));
+        assert!(matches!(error("(-9223372036854775807 - 1) // -1;"), IntegerOverflow));
+        assert_eq!(eval("(-9223372036854775807 - 1) % -1;").unwrap(), Value::Int(0));
+    }
+
+    #[test]
+    fn division_by_zero_is_runtime_error() {
+        assert!(matches!(error("1 / 0;"), DivisionByZero));
+        assert!(matches!(error("1 // 0;"), DivisionByZero));
+        assert!(matches!(error("1 % 0;"), DivisionByZero));
+        assert!(matches!(error("let x <- 1; x /= 0;"), DivisionByZero));
+    }
+
+    #[test]
+    fn if_is_an_expression() {
+        let value = eval("
+            func pick(a, b) -> if a > b { a } else { b };
+            let x <- if pick(3, 7) == 7 { \"seven\" } else { \"other\" };
+            [pick(3, 7), pick(9, 2), x, if false { 1 }];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            Value::Int(7), Value::Int(9), string("seven"), Value::Null
+        ]));
+    }
+
+    #[test]
+    fn block_is_an_expression() {
+        let value = eval("
+            let n <- 1;
+            let y <- {
+                let t <- n * 2;
+                n += 1;
+                t + 1
+            };
+            [y, n, { 4; }, { let unused <- 5; }];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            Value::Int(3), Value::Int(2), Value::Int(4), Value::Null
+        ]));
+        assert!(matches!(error("let y <- { let t <- 1; t }; t;"), NameNotDefined { .. }));
+    }
+
+    #[test]
+    fn curly_brace_starts_map_only_before_key() {
+        assert_eq!(eval("let k <- \"a\"; keys({ k: 1 });").unwrap(), Value::list(vec![string("a")]));
+        assert_eq!(eval("let k <- \"a\"; { k };").unwrap(), string("a"));
+        assert_eq!(eval("{ (1) };").unwrap(), Value::Int(1));
+        assert_eq!(eval("let m <- {}; len(m);").unwrap(), Value::Int(0));
+        assert!(!parses("{ \"a\": 1 };"));
+        assert_eq!(eval("keys({-1: true, 2.5: false});").unwrap(), Value::list(vec![Value::Int(-1), Value::Number(2.5)]));
+    }
+
+    #[test]
+    fn nested_blocks_are_parsed_once() {
+        // Each level used to be parsed twice, once to tell the block
+        // from the map, so this took exponential time. Parser needs
+        // more stack than the test thread has for this depth.
+        let depth = 30;
+        let src = format!("{}1{};", "({ ".repeat(depth), " })".repeat(depth));
+
+        std::thread::Builder::new()
+            .stack_size(64 * 1024 * 1024)
+            .spawn(move || assert_eq!(eval(&src).unwrap(), Value::Int(1)))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn ranges_match_numbers() {
+        let value = eval("
+            func f(n) -> match n {
+                -10..-5 -> \"low\",
+                -5..=-1 -> \"negative\",
+                0 -> \"zero\",
+                1.5..=2.5 -> \"middle\",
+                _ -> \"other\"
+            };
+
+            [f(-10), f(-6), f(-5), f(-1), f(0), f(2), f(2.5), f(3), f(\"-7\")];
+        ");
+
+        assert_eq!(value.unwrap(), strings(&[
+            "low", "low", "negative", "negative", "zero", "middle", "middle", "other", "other"
+        ]));
+    }
+
+    #[test]
+    fn lists_and_records_are_destructured() {
+        let value = eval("
+            func f(v) -> match v {
+                [a, [b, c]] -> \"nested \" + a + b + c,
+                [_, _] -> \"pair\",
+                {\"name\": name, age} if age >= 18 -> name + \" is an adult\",
+                {\"name\": name} -> name + \" is a minor\",
+                _ -> \"other\"
+            };
+
+            [f([1, [2, 3]]), f([1, 2]), f([1, 2, 3]), f({\"name\": \"Al\", \"age\": 20}),
+                f({\"name\": \"Bo\", \"age\": 2}), f({\"age\": 20})];
+        ");
+
+        assert_eq!(value.unwrap(), strings(&[
+            "nested 123", "pair", "other", "Al is an adult", "Bo is a minor", "other"
+        ]));
+    }
+
+    #[test]
+    fn class_patterns_match_its_instances_only() {
+        let value = eval("
+            class Point { let x <- 0; let y <- 0; func init(x, y) -> { self.x <- x; self.y <- y; } }
+            class Other { let x <- 0; let y <- 0; }
+
+            func f(v) -> match v {
+                Point {x: 0, y} -> \"on the y axis at \" + y,
+                Point {x, y} if x == y -> \"diagonal\",
+                Point {} -> \"point\",
+                {x, y} -> \"record\",
+                _ -> \"other\"
+            };
+
+            [f(Point(0, 4)), f(Point(2, 2)), f(Point(1, 2)), f(Other()), f({\"x\": 1, \"y\": 2}), f(1)];
+        ");
+
+        assert_eq!(value.unwrap(), strings(&[
+            "on the y axis at 4", "diagonal", "point", "record", "record", "other"
+        ]));
+    }
+
+    #[test]
+    fn guards_are_tried_in_order() {
+        let value = eval("
+            let calls <- [];
+            func check(n) -> { push(calls, n); return n > 1; }
+            let arm <- match 2 { n if check(1) -> \"first\", n if check(n) -> \"second\", _ -> \"last\" };
+            [arm, calls];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            string("second"),
+            Value::list(vec![Value::Int(1), Value::Int(2)])
+        ]));
+        assert!(matches!(error("match [1, 2, 3] { [a] -> a, n if false -> n };"), NoMatchingArm { .. }));
+    }
+
+    #[test]
+    fn names_are_bound_once_per_pattern() {
+        assert!(!parses("match [1, 2] { [a, a] -> a };"));
+        assert!(!parses("match {\"x\": 1} { {x, \"y\": [x]} -> x };"));
+        assert!(parses("match [1, 2] { [_, _] -> 1, [a, b] -> a, a -> a };"));
+    }
+
+    #[test]
+    fn runtime_errors_are_caught_as_maps() {
+        let value = eval("
+            let error <- null;
+
+            try {
+                let x <- 1;
+                x / 0;
+            } catch e {
+                error <- e;
+            }
+
+            [error[\"kind\"], error[\"message\"], error[\"line\"], keys(error)];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            string("DivisionByZero"),
+            string("Division by zero"),
+            Value::Int(6),
+            strings(&["kind", "message", "line"])
+        ]));
+    }
+
+    #[test]
+    fn thrown_values_cross_function_calls() {
+        let value = eval("
+            let log <- [];
+            func inner() -> { throw {\"kind\": \"Custom\", \"value\": 5}; }
+            func outer() -> { inner(); push(log, \"unreachable\"); }
+
+            try {
+                outer();
+            } catch e {
+                push(log, e[\"value\"]);
+            }
+
+            try {
+                try { throw 1; } catch e { throw e + 1; }
+            } catch e {
+                push(log, e);
+            }
+
+            log;
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![Value::Int(5), Value::Int(2)]));
+    }
+
+    #[test]
+    fn finally_runs_however_try_ends() {
+        let value = eval("
+            let log <- [];
+
+            func f() -> {
+                try { return \"returned\"; } finally { push(log, \"return\"); }
+            }
+
+            push(log, f());
+            let n <- 0;
+
+            loop n < 5 {
+                n += 1;
+
+                try {
+                    if n == 1 { continue; }
+                    break;
+                } finally {
+                    push(log, n);
+                }
+            }
+
+            try {
+                try { throw \"error\"; } finally { push(log, \"throw\"); }
+            } catch e {
+                push(log, e);
+            }
+
+            log;
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            string("return"), string("returned"), Value::Int(1), Value::Int(2),
+            string("throw"), string("error")
+        ]));
+    }
+
+    #[test]
+    fn uncaught_throw_is_runtime_error() {
+        let uncaught = eval("func f() -> { throw [1, 2]; } f();").unwrap_err();
+
+        assert_eq!(uncaught.tag.kind(), "UncaughtThrow");
+        assert_eq!(uncaught.message(), "Uncaught exception `[1, 2]`");
+        assert!(matches!(error("try { throw 1; } catch e { throw e + 1; }"), UncaughtThrow { .. }));
+        assert!(matches!(error("try { 1 / 0; } finally { }"), DivisionByZero));
+    }
+
+    #[test]
+    fn repeat_runs_body_given_number_of_times() {
+        let value = eval("
+            let n <- 0;
+            repeat 3 { n += 1; }
+            n;
+        ");
+
+        assert_eq!(value.unwrap(), Value::Int(3));
+        assert!(matches!(error("repeat -1 { }"), InvalidRepeatCount { .. }));
+        assert!(matches!(error("repeat 2.0 { }"), InvalidRepeatCount { .. }));
+    }
+
+    #[test]
+    fn until_condition_sees_body_variables() {
+        let value = eval("
+            let i <- 0;
+            repeat {
+                i += 1;
+                let done <- i >= 3;
+            } until done;
+            i;
+        ");
+
+        assert_eq!(value.unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn until_condition_is_checked_after_continue() {
+        let value = eval("
+            let i <- 0;
+            repeat {
+                i += 1;
+                let done <- i >= 2;
+                continue;
+            } until done;
+            i;
+        ");
+
+        assert_eq!(value.unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn collections_containing_themselves_are_printed() {
+        let list = eval("
+            let xs <- [1];
+            push(xs, xs);
+            \"{xs}\";
+        ");
+
+        let map = eval("
+            let m <- {\"a\": [1]};
+            m[\"self\"] <- m;
+            \"{m}\";
+        ");
+
+        let instance = eval("
+            class Node { let next <- null; }
+            let node <- Node();
+            node.next <- [node];
+            \"{node}\";
+        ");
+
+        assert_eq!(list.unwrap(), string("[1, [...]]"));
+        assert_eq!(map.unwrap(), string("{\"a\": [1], \"self\": {...}}"));
+        assert_eq!(instance.unwrap(), string("Node {next: [Node {...}]}"));
+    }
+
+    #[test]
+    fn collections_containing_themselves_are_compared() {
+        let value = eval("
+            let a <- [1];
+            push(a, a);
+            let b <- [1];
+            push(b, b);
+            let m <- {};
+            m[\"m\"] <- m;
+            let n <- {};
+            n[\"m\"] <- n;
+            [a == b, a == [1, [1]], m == n, m == {\"m\": {}}];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Boolean(true),
+            Value::Boolean(false)
+        ]));
+    }
+
+    #[test]
+    fn map_keys_are_kept_in_insertion_order() {
+        let value = eval("
+            let m <- {\"b\": 1, \"a\": 2, \"c\": 3};
+            remove(m, \"a\");
+            m[\"a\"] <- 4;
+            m[\"b\"] <- 5;
+            [keys(m), m[\"b\"], len(m)];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            Value::list(vec![string("b"), string("c"), string("a")]),
+            Value::Int(5),
+            Value::Int(3)
+        ]));
+    }
+
+    #[test]
+    fn equal_numbers_are_the_same_key() {
+        let value = eval("
+            let m <- {1: \"int\", true: \"bool\"};
+            m[1.0] <- \"float\";
+            m[0.5] <- \"half\";
+            [m[1], has(m, 1.0), has(m, 2), m[0.5], len(m)];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            string("float"),
+            Value::Boolean(true),
+            Value::Boolean(false),
+            string("half"),
+            Value::Int(3)
+        ]));
+    }
+
+    #[test]
+    fn large_integers_are_compared_with_floats_exactly() {
+        let value = eval("
+            let m <- {};
+            m[9007199254740993] <- \"int\";
+            [
+                9007199254740993 == 9007199254740992.0,
+                9007199254740992 == 9007199254740992.0,
+                9007199254740993 > 9007199254740992.0,
+                9223372036854775807 < 9223372036854775808.0,
+                -3 < -2.5,
+                has(m, 9007199254740992.0)
+            ];
+        ");
+
+        assert_eq!(value.unwrap(), Value::list(vec![
+            Value::Boolean(false),
+            Value::Boolean(true),
+            Value::Boolean(true),
+            Value::Boolean(true),
+            Value::Boolean(true),
+            Value::Boolean(false)
+        ]));
+        assert!(matches!(
+            error("let m <- {}; m[9007199254740993] <- 1; m[9007199254740992.0];"),
+            KeyNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_map_access_is_runtime_error() {
+        assert!(matches!(error("let m <- {\"a\": 1}; m[\"b\"];"), KeyNotFound { .. }));
+        assert!(matches!(error("remove({}, 1);"), KeyNotFound { .. }));
+        assert!(matches!(error("let m <- {}; m[(-1) ^ 0.5] <- 1;"), InvalidMapKey { .. }));
+        assert!(matches!(error("has({}, (-1) ^ 0.5);"), InvalidMapKey { .. }));
+        assert!(matches!(error("let m <- {}; m[[1]] <- 1;"), InvalidMapKey { .. }));
+    }
+
+    #[test]
+    fn minimum_integer_can_be_written() {
+        assert_eq!(eval("-9223372036854775808;").unwrap(), Value::Int(i64::MIN));
+        assert_eq!(eval("-0x8000_0000_0000_0000;").unwrap(), Value::Int(i64::MIN));
+        assert_eq!(
+            eval("match -9223372036854775808 { -9223372036854775808 -> true, _ -> false };").unwrap(),
+            Value::Boolean(true)
+        );
+        assert_eq!(eval("-2 ^ 2;").unwrap(), Value::Int(4));
+        assert!(!parses("9223372036854775808;"));
+        assert!(!parses("match 1 { 9223372036854775808 -> 1 };"));
+    }
+
+    #[test]
+    fn integer_arithmetic_overflows() {
+        assert!(matches!(error("9223372036854775807 + 1;"), IntegerOverflow));
+        assert!(matches!(error("-9223372036854775808 - 1;"), IntegerOverflow));
+        assert!(matches!(error("4611686018427387904 * 2;"), IntegerOverflow));
+        assert!(matches!(error("-(-9223372036854775808);"), IntegerOverflow));
+        assert!(matches!(error("2 ^ 63;"), IntegerOverflow));
+        assert_eq!(eval("2 ^ 62;").unwrap(), Value::Int(1 << 62));
+    }
+
+    #[test]
+    fn powers_of_trivial_bases_never_overflow() {
+        assert_eq!(eval("1 ^ 5000000000;").unwrap(), Value::Int(1));
+        assert_eq!(eval("0 ^ 5000000000;").unwrap(), Value::Int(0));
+        assert_eq!(eval("0 ^ 0;").unwrap(), Value::Int(1));
+        assert_eq!(eval("-1 ^ 5000000000;").unwrap(), Value::Int(1));
+        assert_eq!(eval("-1 ^ 5000000001;").unwrap(), Value::Int(-1));
+        assert!(matches!(error("2 ^ 5000000000;"), IntegerOverflow));
+    }
+
+    #[test]
+    fn mixed_arithmetic_gives_floats() {
+        assert_eq!(eval("2 * 3;").unwrap(), Value::Int(6));
+        assert_eq!(eval("1 + 0.5;").unwrap(), Value::Number(1.5));
+        assert_eq!(eval("0.5 * 4;").unwrap(), Value::Number(2.0));
+        assert_eq!(eval("7 / 2;").unwrap(), Value::Number(3.5));
+        assert_eq!(eval("6 / 3;").unwrap(), Value::Number(2.0));
+        assert_eq!(eval("2 ^ 0.5;").unwrap(), Value::Number(2f64.sqrt()));
+        assert_eq!(eval("9223372036854775807 + 1.0;").unwrap(), Value::Number(9223372036854775808.0));
+    }
+
+    #[test]
+    fn bitwise_operators_work_on_integers() {
+        let value = eval("[12 & 10, 12 | 10, 12 ~ 10, ~0, 1 << 62, -16 >> 2, -1 >> 100, 0 << 64];");
+        let expected = [8, 14, 6, -1, 1 << 62, -4, -1, 0].into_iter().map(Value::Int).collect();
+
+        assert_eq!(value.unwrap(), Value::list(expected));
+        assert_eq!(eval("6 & 4 == 4;").unwrap(), Value::Boolean(true));
+        assert!(matches!(error("1.0 & 1;"), IncompatibleOperands { .. }));
+    }
+
+    #[test]
+    fn shifts_are_checked() {
+        assert!(matches!(error("1 << 63;"), IntegerOverflow));
+        assert!(matches!(error("3 << 62;"), IntegerOverflow));
+        assert!(matches!(error("1 << 64;"), IntegerOverflow));
+        assert_eq!(eval("-1 << 63;").unwrap(), Value::Int(i64::MIN));
+        assert!(matches!(error("1 << -1;"), NegativeShiftCount { .. }));
+        assert!(matches!(error("1 >> -1;"), NegativeShiftCount { .. }));
+        assert!(matches!(error("\"a\" << -1;"), IncompatibleOperands { .. }));
+        assert!(matches!(error("1.5 >> -1;"), IncompatibleOperands { .. }));
+    }
+}
@@ -1,9 +1,20 @@
-use crate::parser::ast::expression::Expression;
+use crate::parser::ast::{
+    expression::Expression,
+    statement::Group
+};
 use super::{
     env::Env,
-    value::Value,
     expression::Evaluatable,
-    runtime_exception::InterpreterException
+    statement::Executable,
+    runtime_exception::{
+        InterpreterException,
+        RuntimeError,
+        RuntimeErrorTag::{self, *}
+    }
+};
+use crate::{
+    errors::Span,
+    lexer::token::Token
 };
 
 
@@ -11,5 +22,57 @@ pub fn check_condition(
     env: &mut Env,
     condition: &Box<Expression>
 ) -> Result<bool, InterpreterException> {
-    Ok(condition.eval(env)?.to_boolean().unwrap() == Value::Boolean(true))
+    Ok(condition.eval(env)?.to_boolean())
+}
+
+/// Run single iteration of the loop body.
+/// Returns `false` if the loop is interrupted with `break`.
+pub fn run_loop_body(
+    env: &mut Env,
+    body: &Group
+) -> Result<bool, InterpreterException> {
+    match body.run(env) {
+        Err(InterpreterException::Break(_)) => Ok(false),
+        Err(InterpreterException::Continue(_)) => Ok(true),
+        Err(exception) => Err(exception),
+        Ok(_) => Ok(true)
+    }
+}
+
+/// Wrap error of `<object>[<index>]` access.
+/// Errors related to the index are reported at the index span,
+/// the others at the object span.
+pub fn index_error(
+    tag: RuntimeErrorTag,
+    object: &Expression,
+    index: &Expression
+) -> InterpreterException {
+    let span = match tag {
+        ObjectIsNotIndexable => Span::from(object.clone()),
+        _ => Span::from(index.clone())
+    };
+
+    InterpreterException::Fatal(RuntimeError {
+        span: span,
+        tag: tag
+    })
+}
+
+/// Wrap error of `<object>.<name>` access.
+/// Errors related to the object are reported at the object span,
+/// the others at the property name.
+pub fn property_error(
+    tag: RuntimeErrorTag,
+    object: &Expression,
+    name: &Token
+) -> InterpreterException {
+    let span = match tag {
+        ObjectHasNoProperties { .. } => Span::from(object.clone()),
+        _ => Span::from(name.clone())
+    };
+
+    InterpreterException::Fatal(RuntimeError {
+        span: span,
+        tag: tag
+    })
 }
@@ -1,146 +1,604 @@
-use std::{rc::Rc, ops};
+use std::{rc::Rc, cell::RefCell, cmp::Ordering, fmt, ops};
 use crate::{
-    parser::ast::statement::Group,
+    parser::ast::statement::{Group, Params},
     lexer::token::Token,
 };
-use super::runtime_exception::{
-    InterpreterException,
-    RuntimeError,
-    RuntimeErrorTag::*
+use super::{
+    runtime_exception::{
+        InterpreterException,
+        RuntimeErrorTag::{self, *}
+    },
+    env::Env,
+    map::Map,
+    object::{Class, Instance},
+    module::Module
 };
 
+/// Value of the script.
+///
+/// Hosts create and inspect the values with the constructors and accessors,
+/// variants of the functions, classes and the like are internal.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Value {
     String(Box<String>),
+    Int(i64),
+    /// Floating point number
     Number(f64),
     Boolean(bool),
     Function {
-        params: Vec<Token>,
-        name: Token,
+        params: Rc<Params>,
+        /// Anonymous functions have no name
+        name: Option<Token>,
         body: Rc<Group>,
+        /// Environment the function is defined in
+        closure: Env,
     },
+    NativeFunction(NativeFunction),
+    /// Lists are shared, mutation is visible through every reference
+    List(Rc<RefCell<Vec<Value>>>),
+    /// Maps are shared the same way lists are
+    Map(Rc<RefCell<Map>>),
+    Class(Rc<Class>),
+    /// Instances are shared, as well as lists and maps
+    Instance(Rc<RefCell<Instance>>),
+    /// Namespace of the imported module
+    Module(Rc<Module>),
     Null,
 }
 
+/// Function implemented in Rust, either built-in or registered by the host
+#[derive(Clone)]
+pub struct NativeFunction {
+    pub name: String,
+    pub arity: usize,
+    /// Called with exactly `arity` arguments
+    pub func: Rc<dyn Fn(Vec<Value>) -> Result<Value, RuntimeErrorTag>>
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NativeFunction({}/{})", self.name, self.arity)
+    }
+}
+
+/// Compare the integer with the float exactly. Converting the integer
+/// to float would round the large ones, e.g. `2^53 + 1` to `2^53`.
+fn compare_int_float(int: i64, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+
+    // Bounds are powers of two, so they are exact
+    if float < i64::MIN as f64 {
+        return Some(Ordering::Greater);
+    }
+
+    if float >= -(i64::MIN as f64) {
+        return Some(Ordering::Less);
+    }
+
+    let whole = float.trunc();
+    let fraction = if float > whole {
+        Ordering::Less
+    } else if float < whole {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    };
+
+    Some(int.cmp(&(whole as i64)).then(fraction))
+}
+
+/// Error of the operation on values
+#[derive(Debug)]
+pub enum OperationError {
+    /// Operation is not defined for the operand types
+    Incompatible,
+    /// Integer result doesn't fit into 64 bits
+    Overflow
+}
+
 impl ops::Add<Value> for Value {
-    type Output = Result<Value, ()>;
+    type Output = Result<Value, OperationError>;
 
     fn add(self, rhs: Value) -> Self::Output {
         match (self, rhs) {
-            (Value::Number(l), Value::Number(r)) => {
-                Ok(Value::Number(l + r))
-            },
             (Value::String(l), Value::String(r)) => {
                 Ok(Value::String(
                     Box::new(l.as_ref().clone() + r.as_ref())
                 ))
             },
-            (Value::Number(l), Value::String(r)) => {
+            (l @ (Value::Int(_) | Value::Number(_)), Value::String(r)) => {
                 Ok(Value::String(
-                    Box::new(l.to_string() + r.as_ref())
+                    Box::new(l.to_number_string() + r.as_ref())
                 ))
             },
-            (Value::String(l), Value::Number(r)) => {
+            (Value::String(l), r @ (Value::Int(_) | Value::Number(_))) => {
                 Ok(Value::String(
-                    Box::new(l.as_ref().clone() + &r.to_string())
+                    Box::new(l.as_ref().clone() + &r.to_number_string())
                 ))
             },
-            _ => Err(())
+            (l, r) => l.arithmetic(r, i64::checked_add, |l, r| l + r)
         }
     }
 }
 
 impl ops::Sub for Value {
-    type Output = Result<Value, ()>;
+    type Output = Result<Value, OperationError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(l), Value::Number(r)) => {
-                Ok(Value::Number(l - r))
-            },
-            _ => Err(()),
-        }
+        self.arithmetic(rhs, i64::checked_sub, |l, r| l - r)
     }
 }
 
 impl ops::Neg for Value {
-    type Output = Result<Value, ()>;
+    type Output = Result<Value, OperationError>;
 
     fn neg(self) -> Self::Output {
         match self {
+            Value::Int(n) => n.checked_neg().map(Value::Int).ok_or(OperationError::Overflow),
             Value::Number(n) => Ok(Value::Number(-n)),
-            _ => Err(())
+            _ => Err(OperationError::Incompatible)
         }
     }
 }
 
+/// Division is always done in floats, even if both operands are integers
 impl ops::Div for Value {
-    type Output =  Result<Value, ()>;
+    type Output =  Result<Value, OperationError>;
 
     fn div(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(l), Value::Number(r)) => {
-                Ok(Value::Number(l / r))
-            },
-            _ => Err(())
+        match (self.to_float(), rhs.to_float()) {
+            (Some(l), Some(r)) => Ok(Value::Number(l / r)),
+            _ => Err(OperationError::Incompatible)
         }
     }
 }
 
 impl ops::Mul for Value {
-    type Output = Result<Value, ()>;
+    type Output = Result<Value, OperationError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::Number(l), Value::Number(r)) => {
-                Ok(Value::Number(l * r))
+        self.arithmetic(rhs, i64::checked_mul, |l, r| l * r)
+    }
+}
+
+/// Remainder of the floored division, so it has the same sign
+/// as the divisor, e.g. `-7 % 2 == 1` and `7 % -2 == -1`
+impl ops::Rem for Value {
+    type Output = Result<Value, OperationError>;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.arithmetic(
+            rhs,
+            |l, r| {
+                // `i64::MIN % -1` overflows in Rust, though the remainder is zero
+                let rem = if r == -1 { 0 } else { l.checked_rem(r)? };
+
+                if rem != 0 && (rem < 0) != (r < 0) {
+                    Some(rem + r)
+                } else {
+                    Some(rem)
+                }
             },
-            _ => Err(()),
-        }
+            |l, r| {
+                let rem = l % r;
+
+                if rem != 0.0 && (rem < 0.0) != (r < 0.0) {
+                    rem + r
+                } else {
+                    rem
+                }
+            }
+        )
     }
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut vec![])
+    }
+}
+
+impl Value {
+    /// Compare the values. Pairs of collections being compared already
+    /// (`path`) are considered equal, so the ones containing themselves
+    /// are compared without infinite recursion.
+    pub fn equals(&self, other: &Value, path: &mut Vec<(*const (), *const ())>) -> bool {
         match (self, other) {
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
             (Value::Number(a), Value::Number(b)) => a == b,
+            // Integers are equal to floats with exactly the same value
+            (Value::Int(a), Value::Number(b)) | (Value::Number(b), Value::Int(a)) => {
+                compare_int_float(*a, *b) == Some(Ordering::Equal)
+            },
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::List(a), Value::List(b)) => {
+                let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+                if Rc::ptr_eq(a, b) || path.contains(&pair) {
+                    return true;
+                }
+
+                let (a, b) = (a.borrow(), b.borrow());
+
+                path.push(pair);
+                let equal = a.len() == b.len()
+                    && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b, path));
+                path.pop();
+
+                equal
+            },
+            (Value::Map(a), Value::Map(b)) => {
+                let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+                if Rc::ptr_eq(a, b) || path.contains(&pair) {
+                    return true;
+                }
+
+                path.push(pair);
+                let equal = a.borrow().equals(&b.borrow(), path);
+                path.pop();
+
+                equal
+            },
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            // Instances are equal only if they are the same object
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            (Value::Module(a), Value::Module(b)) => Rc::ptr_eq(a, b),
+            (Value::Null, Value::Null) => true,
             _ => false
         }
     }
 }
 
 impl Value {
-    pub fn to_boolean(&self) -> Result<Value, RuntimeError> {
+    pub fn string(value: impl Into<String>) -> Value {
+        Value::String(Box::new(value.into()))
+    }
+
+    pub fn list(items: Vec<Value>) -> Value {
+        Value::List(Rc::new(RefCell::new(items)))
+    }
+
+    pub(crate) fn map(map: Map) -> Value {
+        Value::Map(Rc::new(RefCell::new(map)))
+    }
+
+    /// Map of the entries, the later of the entries with equal keys wins.
+    ///
+    /// # Errors
+    /// Returns the error message if any of the keys is not a string,
+    /// number or boolean, or it is `NaN`.
+    pub fn from_entries<I>(entries: I) -> Result<Value, String>
+    where
+        I: IntoIterator<Item = (Value, Value)>
+    {
+        let mut map = Map::new();
+
+        for (key, value) in entries {
+            map.insert(key, value).map_err(|tag| tag.to_human_readable())?;
+        }
+
+        Ok(Value::map(map))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(str) => Some(str),
+            _ => None
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Copy of the list items. The list itself is shared with the script,
+    /// so it may change later.
+    pub fn items(&self) -> Option<Vec<Value>> {
+        match self {
+            Value::List(list) => Some(list.borrow().clone()),
+            _ => None
+        }
+    }
+
+    /// Copy of the map entries in the insertion order
+    pub fn entries(&self) -> Option<Vec<(Value, Value)>> {
         match self {
+            Value::Map(map) => Some(map.borrow().entries().clone()),
+            _ => None
+        }
+    }
+
+    /// Apply arithmetic operation to numbers. Integers are combined
+    /// with `int`, which returns `None` on overflow, if any of the operands
+    /// is float, both are converted to floats and combined with `float`.
+    fn arithmetic(
+        self,
+        rhs: Value,
+        int: fn(i64, i64) -> Option<i64>,
+        float: fn(f64, f64) -> f64
+    ) -> Result<Value, OperationError> {
+        match (&self, &rhs) {
+            (Value::Int(l), Value::Int(r)) => {
+                int(*l, *r).map(Value::Int).ok_or(OperationError::Overflow)
+            },
+            _ => match (self.to_float(), rhs.to_float()) {
+                (Some(l), Some(r)) => Ok(Value::Number(float(l, r))),
+                _ => Err(OperationError::Incompatible)
+            }
+        }
+    }
+
+    /// Floored division, i.e. the quotient is rounded towards negative
+    /// infinity, e.g. `-7 // 2 == -4`. For floats the result is float
+    /// with no fractional part.
+    pub fn floor_div(self, rhs: Value) -> Result<Value, OperationError> {
+        self.arithmetic(
+            rhs,
+            |l, r| {
+                let quotient = l.checked_div(r)?;
+
+                if l % r != 0 && (l < 0) != (r < 0) {
+                    Some(quotient - 1)
+                } else {
+                    Some(quotient)
+                }
+            },
+            |l, r| (l / r).floor()
+        )
+    }
+
+    /// Numeric value as float, `None` if the value is not a number
+    pub fn to_float(&self) -> Option<f64> {
+        match self {
+            Value::Int(n) => Some(*n as f64),
+            Value::Number(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    /// Compare numbers, `None` if any of the values is not a number
+    /// or they are not comparable, i.e. `NaN`
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Int(l), Value::Int(r)) => Some(l.cmp(r)),
+            (Value::Int(l), Value::Number(r)) => compare_int_float(*l, *r),
+            (Value::Number(l), Value::Int(r)) => Some(compare_int_float(*r, *l)?.reverse()),
+            _ => self.to_float()?.partial_cmp(&other.to_float()?)
+        }
+    }
+
+    /// Floats are always printed with the fractional part,
+    /// so they are distinguishable from integers
+    fn to_number_string(&self) -> String {
+        match self {
+            Value::Int(n) => format!("{n}"),
+            Value::Number(n) => format!("{n:?}"),
+            _ => unreachable!()
+        }
+    }
+
+    /// Name of the value type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Int(_) => "int",
+            Value::Number(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::Function { .. } | Value::NativeFunction(_) => "function",
+            Value::List(_) => "list",
+            Value::Map(_) => "map",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "object",
+            Value::Module(_) => "module",
+            Value::Null => "null",
+        }
+    }
+
+    /// Truthiness of the value.
+    /// Empty strings, empty lists and maps, zero, `false` and `null` are falsy,
+    /// everything else is truthy.
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            Value::String(str) => *str.as_ref() != "",
+            Value::Int(n) => *n != 0,
+            Value::Number(n) => *n != 0.0,
+            Value::Boolean(boolean) => *boolean,
+            Value::Function { .. } | Value::NativeFunction(_) => true,
+            Value::List(list) => !list.borrow().is_empty(),
+            Value::Map(map) => !map.borrow().is_empty(),
+            Value::Class(_) | Value::Instance(_) | Value::Module(_) => true,
+            Value::Null => false,
+        }
+    }
+
+    /// Convert value to index of the collection of length `len`.
+    ///
+    /// Floats with no fractional part are accepted as well.
+    ///
+    /// # Errors
+    /// Emits `InvalidIndex` if value is not an integer and
+    /// `IndexOutOfRange` if it's not in `0..len`.
+    pub fn to_index(&self, len: usize) -> Result<usize, RuntimeErrorTag> {
+        match self {
+            Value::Int(n) => {
+                if *n >= 0 && (*n as u64) < len as u64 {
+                    Ok(*n as usize)
+                } else {
+                    Err(IndexOutOfRange { index: format!("{n}") })
+                }
+            },
+            Value::Number(n) if n.fract() == 0.0 => {
+                if *n >= 0.0 && *n < len as f64 {
+                    Ok(*n as usize)
+                } else {
+                    Err(IndexOutOfRange { index: format!("{n}") })
+                }
+            },
+            _ => Err(InvalidIndex {
+                value: self
+                    .to_repr()
+                    .unwrap_or_else(|_| self.type_name().into())
+            })
+        }
+    }
+
+    /// Get element of the collection, i.e. `value[index]`.
+    /// For maps `index` is the key.
+    pub fn get_item(&self, index: &Value) -> Result<Value, RuntimeErrorTag> {
+        match self {
+            Value::List(list) => {
+                let list = list.borrow();
+                Ok(list[index.to_index(list.len())?].clone())
+            },
             Value::String(str) => {
-                Ok(Value::Boolean(*str.as_ref() != ""))
+                let ch = str.chars().nth(index.to_index(str.chars().count())?);
+                Ok(Value::String(Box::new(ch.unwrap().into())))
             },
-            Value::Number(n) => {
-                Ok(Value::Boolean(*n != 0.0))
+            Value::Map(map) => map.borrow().get(index),
+            _ => Err(ObjectIsNotIndexable)
+        }
+    }
+
+    /// Set element of the collection, i.e. `value[index] <- item`.
+    /// Assigning to a missing key of the map adds new entry.
+    pub fn set_item(&self, index: &Value, item: Value) -> Result<(), RuntimeErrorTag> {
+        match self {
+            Value::List(list) => {
+                let mut list = list.borrow_mut();
+                let index = index.to_index(list.len())?;
+                list[index] = item;
+                Ok(())
             },
-            Value::Boolean(_) => Ok(self.clone()),
-            _ => todo!()
+            Value::Map(map) => map.borrow_mut().insert(index.clone(), item),
+            _ => Err(ObjectIsNotIndexable)
         }
     }
 
+    /// Get property of the object, i.e. `value.name`
+    pub fn get_property(&self, name: &str) -> Result<Value, RuntimeErrorTag> {
+        match self {
+            Value::Instance(instance) => Instance::get(instance, name),
+            Value::Module(module) => module.get(name),
+            _ => Err(ObjectHasNoProperties {
+                type_name: self.type_name().into()
+            })
+        }
+    }
+
+    /// Set property of the object, i.e. `value.name <- item`
+    pub fn set_property(&self, name: &str, item: Value) -> Result<(), RuntimeErrorTag> {
+        match self {
+            Value::Instance(instance) => instance.borrow_mut().set(name, item),
+            Value::Module(module) => Err(ReadOnlyModule {
+                name: module.name.clone()
+            }),
+            _ => Err(ObjectHasNoProperties {
+                type_name: self.type_name().into()
+            })
+        }
+    }
+
+    /// String representation of the value inside the collection,
+    /// where strings are quoted
+    pub fn to_repr(&self) -> Result<String, InterpreterException> {
+        self.format(true, &mut vec![])
+    }
+
     pub fn to_string(&self) -> Result<String, InterpreterException> {
+        self.format(false, &mut vec![])
+    }
+
+    /// Format the value, quoting it if it's a string and `quoted` is set.
+    /// Collections being formatted already (`path`) are not entered again,
+    /// so the ones containing themselves are printed like `[1, [...]]`.
+    fn format(&self, quoted: bool, path: &mut Vec<*const ()>) -> Result<String, InterpreterException> {
         match self {
+            Value::String(str) if quoted => Ok(format!("{:?}", str)),
             Value::String(str) => Ok(format!("{str}")),
-            Value::Number(n) => Ok(format!("{n}")),
+            Value::Int(_) | Value::Number(_) => Ok(self.to_number_string()),
             Value::Boolean(boolean) => Ok(format!("{boolean}")),
-            _ => {
-                Err(InterpreterException::Fatal(
-                    RuntimeError {
-                        span: todo!(),
-                        tag: ConversionError {
-                            from: format!("{:?}", self),
-                            to: "String".into(),
-                        }
-                    }
-                ))
-            }
+            Value::Function { name: Some(name), .. } => {
+                Ok(format!("<func {}>", name.get_lexeme()))
+            },
+            Value::Function { name: None, .. } => Ok("<func>".into()),
+            Value::NativeFunction(native) => Ok(format!("<native {}>", native.name)),
+            Value::List(list) => {
+                let ptr = Rc::as_ptr(list) as *const ();
+
+                if path.contains(&ptr) {
+                    return Ok("[...]".into());
+                }
+
+                path.push(ptr);
+                let items = list
+                    .borrow()
+                    .iter()
+                    .map(|item| item.format(true, path))
+                    .collect::<Result<Vec<String>, InterpreterException>>();
+                path.pop();
+
+                Ok(format!("[{}]", items?.join(", ")))
+            },
+            Value::Map(map) => {
+                let ptr = Rc::as_ptr(map) as *const ();
+
+                if path.contains(&ptr) {
+                    return Ok("{...}".into());
+                }
+
+                path.push(ptr);
+                let entries = map
+                    .borrow()
+                    .entries()
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(format!("{}: {}", key.format(true, path)?, value.format(true, path)?))
+                    })
+                    .collect::<Result<Vec<String>, InterpreterException>>();
+                path.pop();
+
+                Ok(format!("{{{}}}", entries?.join(", ")))
+            },
+            Value::Class(class) => Ok(format!("<class {}>", class.name)),
+            Value::Module(module) => Ok(format!("<module {}>", module.name)),
+            Value::Instance(instance) => {
+                let ptr = Rc::as_ptr(instance) as *const ();
+                let object = instance.borrow();
+
+                if path.contains(&ptr) {
+                    return Ok(format!("{} {{...}}", object.class.name));
+                }
+
+                path.push(ptr);
+                let fields = object
+                    .fields
+                    .iter()
+                    .map(|(name, value)| Ok(format!("{}: {}", name, value.format(true, path)?)))
+                    .collect::<Result<Vec<String>, InterpreterException>>();
+                path.pop();
+
+                Ok(format!("{} {{{}}}", object.class.name, fields?.join(", ")))
+            },
+            Value::Null => Ok("null".into()),
         }
     }
 }
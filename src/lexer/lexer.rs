@@ -1,6 +1,5 @@
 use std::rc::Rc;
 use crate::{
-    file_stream::FileStream,
     errors::DebugInfo,
     lexer::token::{
         Token,
@@ -8,8 +7,10 @@ use crate::{
         Lexeme
     }
 };
-use super::lexical_error::{LexicalError, LexicalErrorTag::*};
+use super::lexical_error::{LexicalError, LexicalErrorTag::{self, *}};
 
+/// Positions `curr` and `start` are byte offsets in the source,
+/// while `col` counts characters.
 pub struct Lexer {
     pub src: Rc<String>,
     pub curr: usize,
@@ -17,30 +18,44 @@ pub struct Lexer {
     pub line: usize,
     pub col: usize,
     pub fname: String,
+    /// Line and column where the current token starts
+    start_line: usize,
+    start_col: usize,
+    /// Interpolated strings whose expressions are being lexed,
+    /// the innermost is the last
+    interpolations: Vec<Interpolation>,
+}
+
+/// Expression embedded into the string literal, e.g. `"a {b} c"`
+struct Interpolation {
+    /// Number of curly braces opened inside the expression,
+    /// closing brace at zero depth continues the string
+    depth: usize,
+    /// Position of the opening quote
+    start: usize,
+    line: usize,
+    col: usize,
 }
 
 impl Lexer {
-    pub fn from_file(path: &String) -> Self {
+    /// Lexer of the source read from the file `fname`,
+    /// which is reported in errors and used to resolve imports
+    pub fn new(src: String, fname: String) -> Self {
         Self {
-            src: Rc::new(FileStream::new(path).as_str()),
+            src: Rc::new(src),
             curr: 0,
             start: 0,
             line: 1,
             col: 0,
-            fname: path.clone()
+            fname: fname,
+            start_line: 1,
+            start_col: 0,
+            interpolations: vec![]
         }
     }
 
-    #[allow(dead_code)]
     pub fn from_string(string: String) -> Self {
-        Self {
-            src: Rc::new(string),
-            curr: 0,
-            start: 0,
-            line: 1,
-            col: 0,
-            fname: "unnamed".into()
-        }
+        Self::new(string, "unnamed".into())
     }
 
     pub fn lex(&mut self) -> Result<Vec<Token>, Vec<LexicalError>> {
@@ -71,16 +86,48 @@ impl Lexer {
 
     fn lex_token(&mut self) -> Result<Token, LexicalError> {
         self.start = self.curr;
+        self.start_line = self.line;
+        self.start_col = self.col;
 
         if let Some(ch) = self.accept() {
             let token_tag = match ch {
                 ' ' | '\n' => return self.lex_token(),
                 '(' => TokenTag::LeftParen,
                 ')' => TokenTag::RightParen,
-                '{' => TokenTag::LeftCurly,
-                '}' => TokenTag::RightCurly,
-                '.' => TokenTag::Dot,
+                '{' => {
+                    if let Some(interpolation) = self.interpolations.last_mut() {
+                        interpolation.depth += 1;
+                    }
+
+                    TokenTag::LeftCurly
+                },
+                '}' => match self.interpolations.last_mut() {
+                    Some(interpolation) if interpolation.depth == 0 => {
+                        self.lex_string(true)?
+                    },
+                    Some(interpolation) => {
+                        interpolation.depth -= 1;
+                        TokenTag::RightCurly
+                    },
+                    None => TokenTag::RightCurly
+                },
+                '[' => TokenTag::LeftSquare,
+                ']' => TokenTag::RightSquare,
+                '.' => {
+                    if self.match_next('.') {
+                        if self.match_next('=') {
+                            TokenTag::DotDotEqual
+                        } else if self.match_next('.') {
+                            TokenTag::DotDotDot
+                        } else {
+                            TokenTag::DotDot
+                        }
+                    } else {
+                        TokenTag::Dot
+                    }
+                },
                 ',' => TokenTag::Comma,
+                ':' => TokenTag::Colon,
                 ';' => TokenTag::Semicolon,
                 '^' => TokenTag::Circ,
                 '+' => {
@@ -97,9 +144,15 @@ impl Lexer {
                         TokenTag::Star
                     }
                 },
+                '%' => TokenTag::Percent,
+                '&' => TokenTag::Ampersand,
+                '|' => TokenTag::Pipe,
+                '~' => TokenTag::Tilde,
                 '/' => {
                     if self.match_next('=') {
                         TokenTag::SlashEqual
+                    } else if self.match_next('/') {
+                        TokenTag::SlashSlash
                     } else {
                         TokenTag::Slash
                     }
@@ -128,6 +181,8 @@ impl Lexer {
                         TokenTag::ArrowLeft
                     } else if self.match_next('=') {
                         TokenTag::LessEqual
+                    } else if self.match_next('<') {
+                        TokenTag::LessLess
                     } else {
                         TokenTag::Less
                     }
@@ -135,6 +190,8 @@ impl Lexer {
                 '>' => {
                     if self.match_next('=') {
                         TokenTag::GreaterEqual
+                    } else if self.match_next('>') {
+                        TokenTag::GreaterGreater
                     } else {
                         TokenTag::Greater
                     }
@@ -146,8 +203,8 @@ impl Lexer {
                         TokenTag::Equal
                     }
                 },
-                '"' => self.lex_string(),
-                '0'..='9' => self.lex_number(),
+                '"' => self.lex_string(false)?,
+                '0'..='9' => self.lex_number()?,
 
                 // Identifiers and keywords
                 'A'..='Z' | 'a'..='z' | '_' => self.lex_keyword(),
@@ -160,23 +217,51 @@ impl Lexer {
             };
 
             Ok(self.create_token(token_tag))
+        } else if let Some(interpolation) = self.interpolations.pop() {
+            // Input ended inside of the interpolated expression
+            Err(LexicalError {
+                token: self.create_token_from(
+                    interpolation.start,
+                    interpolation.line,
+                    interpolation.col,
+                    TokenTag::Error
+                ),
+                tag: UnterminatedString
+            })
         } else {
             Ok(self.create_token(TokenTag::EndOfFile))
         }
     }
 
     fn create_token(&self, tag: TokenTag) -> Token {
-        let len = self.curr-self.start;
+        self.create_token_from(self.start, self.start_line, self.start_col, tag)
+    }
+
+    /// Create token starting at the given position and ending at the
+    /// current one. Tokens spanning several lines are highlighted
+    /// up to the end of the first line.
+    fn create_token_from(
+        &self,
+        start: usize,
+        line: usize,
+        col: usize,
+        tag: TokenTag
+    ) -> Token {
+        let len = self.src[start..self.curr]
+            .split('\n')
+            .next()
+            .map_or(0, |line| line.chars().count());
+
         Token {
             tag: tag,
             lexeme: Lexeme {
-                start: self.start,
+                start: start,
                 end: self.curr
             },
             info: DebugInfo {
                 fname: self.fname.clone(),
-                line: self.line,
-                col: self.col-len+1,
+                line: line,
+                col: col+1,
                 len: len,
                 src: self.src.clone()
             }
@@ -185,14 +270,40 @@ impl Lexer {
 
     fn lex_keyword(&mut self) -> TokenTag {
         match self.prev().unwrap() {
+            'a' => self.accept_keyword("nd", TokenTag::And),
+            'b' => self.accept_keyword("reak", TokenTag::Break),
+            'c' => {
+                match self.current() {
+                    Some('a') => self.accept_keyword("atch",TokenTag::Catch),
+                    Some('l') => self.accept_keyword("lass",TokenTag::Class),
+                    Some('o') => self.accept_keyword("ontinue",TokenTag::Continue),
+                    _ => self.lex_identifier()
+                }
+            },
             'e' => self.accept_keyword("lse", TokenTag::Else),
             'f' => {
                 match self.current() {
                     Some('a') => self.accept_keyword("alse",TokenTag::False),
+                    Some('i') => self.accept_keyword("inally",TokenTag::Finally),
                     Some('u') => self.accept_keyword("unc",TokenTag::Func),
                     _ => self.lex_identifier()
                 }
             }
+            'm' => self.accept_keyword(
+                "atch",
+                TokenTag::Match
+            ),
+            'n' => {
+                match self.current() {
+                    Some('o') => self.accept_keyword("ot",TokenTag::Not),
+                    Some('u') => self.accept_keyword("ull",TokenTag::Null),
+                    _ => self.lex_identifier()
+                }
+            },
+            'o' => self.accept_keyword(
+                "r",
+                TokenTag::Or
+            ),
             'p' => self.accept_keyword(
                 "rint",
                 TokenTag::Print
@@ -204,14 +315,24 @@ impl Lexer {
                     _ => self.lex_identifier()
                 }
             },
-            't' => self.accept_keyword(
-                "rue",
-                TokenTag::True
-            ),
-            'i' => self.accept_keyword(
-                "f",
-                TokenTag::If
-            ),
+            't' => {
+                match self.current() {
+                    Some('h') => self.accept_keyword("hrow", TokenTag::Throw),
+                    Some('r') => match self.next() {
+                        Some('u') => self.accept_keyword("rue", TokenTag::True),
+                        Some('y') => self.accept_keyword("ry", TokenTag::Try),
+                        _ => self.lex_identifier()
+                    },
+                    _ => self.lex_identifier()
+                }
+            },
+            'i' => {
+                match self.current() {
+                    Some('f') => self.accept_keyword("f", TokenTag::If),
+                    Some('m') => self.accept_keyword("mport", TokenTag::Import),
+                    _ => self.lex_identifier()
+                }
+            },
             'r' => {
                 match self.current() {
                     Some('e') => match self.next() {
@@ -228,6 +349,10 @@ impl Lexer {
                     _ => self.lex_identifier()
                 }
             },
+            'u' => self.accept_keyword(
+                "ntil",
+                TokenTag::Until
+            ),
             _ => self.lex_identifier()
         }
     }
@@ -255,53 +380,270 @@ impl Lexer {
         TokenTag::Identifier(name)
     }
 
-    fn lex_string(&mut self) -> TokenTag {
-        while self.accept().unwrap() != '"' {
-            if self.current().is_none() {
-                panic!("Unterminated string");
+    /// Lex string literal, decoding the escape sequences.
+    ///
+    /// Unescaped `{` starts the interpolated expression, so the string is
+    /// split into parts: `"a {b} c {d} e"` is lexed into `StringStart("a ")`,
+    /// tokens of `b`, `StringMiddle(" c ")`, tokens of `d` and `StringEnd(" e")`.
+    /// `continued` is set if the string is continued after the expression.
+    ///
+    /// # Errors
+    /// Emits `UnterminatedString` if input ends before the closing quote
+    /// and `InvalidEscape` for the first malformed escape sequence.
+    /// In the latter case the rest of the string is still consumed,
+    /// so it is not lexed as the code.
+    fn lex_string(&mut self, continued: bool) -> Result<TokenTag, LexicalError> {
+        let mut string = String::new();
+        let mut error = None;
+
+        let tag = loop {
+            match self.accept() {
+                Some('"') if continued => {
+                    self.interpolations.pop();
+                    break TokenTag::StringEnd(string);
+                },
+                Some('"') => break TokenTag::String(string),
+                Some('{') if continued => {
+                    self.interpolations.last_mut().unwrap().depth = 0;
+                    break TokenTag::StringMiddle(string);
+                },
+                Some('{') => {
+                    self.interpolations.push(Interpolation {
+                        depth: 0,
+                        start: self.start,
+                        line: self.start_line,
+                        col: self.start_col
+                    });
+                    break TokenTag::StringStart(string);
+                },
+                Some('\\') => {
+                    let (start, line, col) = (self.curr-1, self.line, self.col-1);
+
+                    match self.lex_escape() {
+                        Some(ch) => string.push(ch),
+                        None => if error.is_none() {
+                            error = Some(LexicalError {
+                                token: self.create_token_from(
+                                    start, line, col, TokenTag::Error
+                                ),
+                                tag: InvalidEscape
+                            });
+                        }
+                    }
+                },
+                Some(ch) => string.push(ch),
+                None => {
+                    // Continued string is reported from its opening quote
+                    let token = match continued {
+                        true => {
+                            let interpolation = self.interpolations.pop().unwrap();
+
+                            self.create_token_from(
+                                interpolation.start,
+                                interpolation.line,
+                                interpolation.col,
+                                TokenTag::Error
+                            )
+                        },
+                        false => self.create_token(TokenTag::Error)
+                    };
+
+                    return Err(LexicalError {
+                        token: token,
+                        tag: UnterminatedString
+                    });
+                }
             }
         };
 
-        TokenTag::String(String::from(&self.src[self.start+1..self.curr-1]))
+        match error {
+            Some(error) => Err(error),
+            None => Ok(tag)
+        }
     }
 
-    fn lex_number(&mut self) -> TokenTag {
-        let ch = self.prev().unwrap();
-        let mut mantissa: f64 = f64::from(ch.to_digit(10).unwrap());
-        let mut exp = 0;
+    /// Decode escape sequence following the backslash.
+    /// Returns `None` if the sequence is invalid.
+    fn lex_escape(&mut self) -> Option<char> {
+        let ch = match self.current()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '{' => '{',
+            '}' => '}',
+            'u' => {
+                self.accept();
+                return self.lex_unicode_escape();
+            },
+            _ => {
+                self.accept();
+                return None;
+            }
+        };
 
-        while let Some(digit) = self.current() {
-            if !digit.is_ascii_digit() {
+        self.accept();
+        Some(ch)
+    }
+
+    /// Decode `\u{XXXX}` escape, where `XXXX` is 1 to 6 hex digits
+    fn lex_unicode_escape(&mut self) -> Option<char> {
+        if !self.match_next('{') {
+            return None;
+        }
+
+        let mut code = String::new();
+
+        while let Some(ch) = self.current() {
+            if !ch.is_ascii_hexdigit() {
                 break;
             }
 
-            mantissa *= 10.0;
-            mantissa += self.lex_digit();
+            code.push(self.accept().unwrap());
+        }
+
+        if !self.match_next('}') || code.is_empty() || code.len() > 6 {
+            return None;
+        }
+
+        char::from_u32(u32::from_str_radix(&code, 16).ok()?)
+    }
+
+    /// Lex number literal. Literals with fraction or exponent,
+    /// e.g. `3.0` or `1.5e-3`, are floats, the others are integers,
+    /// which may be written in hex, binary or octal as well, e.g. `0xFF`,
+    /// `0b1010`, `0o17`. Digits can be separated with underscores,
+    /// e.g. `1_000_000`.
+    ///
+    /// # Errors
+    /// Emits `MissingDigits`, `MissingExponent`, `InvalidSeparator`,
+    /// `InvalidDigit` or `NumberOutOfRange` for malformed literal,
+    /// which is consumed entirely.
+    fn lex_number(&mut self) -> Result<TokenTag, LexicalError> {
+        let radix = match (self.prev(), self.current()) {
+            (Some('0'), Some('x')) => 16,
+            (Some('0'), Some('b')) => 2,
+            (Some('0'), Some('o')) => 8,
+            _ => 10
         };
 
-        if self.current() == Some('.') {
+        if radix != 10 {
             self.accept();
 
-            while let Some(digit) = self.current() {
-                if !digit.is_ascii_digit() {
-                    break;
-                }
+            let digits = self.lex_digits(radix, String::new())?;
+
+            if digits.is_empty() {
+                return Err(self.number_error(MissingDigits));
+            }
+
+            self.check_number_end()?;
+
+            return self.parse_integer(&digits, radix);
+        }
+
+        // First digit is already accepted
+        let first = self.prev().unwrap().to_string();
+        let mut literal = self.lex_digits(10, first)?;
+        let mut is_float = false;
+
+        if self.current() == Some('.') && self.next().is_some_and(|ch| ch.is_ascii_digit()) {
+            is_float = true;
+            literal.push(self.accept().unwrap());
+            let fraction = self.lex_digits(10, String::new())?;
+            literal.push_str(&fraction);
+        }
+
+        if matches!(self.current(), Some('e' | 'E')) {
+            is_float = true;
+            literal.push(self.accept().unwrap());
+
+            if matches!(self.current(), Some('+' | '-')) {
+                literal.push(self.accept().unwrap());
+            }
+
+            let exponent = self.lex_digits(10, String::new())?;
+
+            if exponent.is_empty() {
+                return Err(self.number_error(MissingExponent));
+            }
+
+            literal.push_str(&exponent);
+        }
 
-                exp += 1;
-                mantissa *= 10.0;
-                mantissa += self.lex_digit();
+        self.check_number_end()?;
+
+        if !is_float {
+            return self.parse_integer(&literal, 10);
+        }
+
+        // Standard library parsing is correctly rounded
+        Ok(TokenTag::Number(literal.parse().unwrap()))
+    }
+
+    /// Parse the digits of the integer literal.
+    /// Magnitude of `i64::MIN` is lexed as `i64::MIN`, so it can be negated,
+    /// the parser rejects it unless it's negated.
+    fn parse_integer(&mut self, digits: &str, radix: u32) -> Result<TokenTag, LexicalError> {
+        match u64::from_str_radix(digits, radix) {
+            Ok(n) if n <= i64::MAX as u64 => Ok(TokenTag::Integer(n as i64)),
+            Ok(n) if n == i64::MIN.unsigned_abs() => Ok(TokenTag::Integer(i64::MIN)),
+            _ => Err(self.number_error(NumberOutOfRange))
+        }
+    }
+
+    /// Lex digits of the given `radix`, skipping the separators.
+    /// Lexed digits are appended to `digits`.
+    fn lex_digits(&mut self, radix: u32, mut digits: String) -> Result<String, LexicalError> {
+        while let Some(ch) = self.current() {
+            if ch.is_digit(radix) {
+                digits.push(self.accept().unwrap());
+            } else if ch == '_' {
+                self.accept();
+
+                // Separator is allowed only between two digits
+                let between_digits = !digits.is_empty()
+                    && self.current().is_some_and(|ch| ch.is_digit(radix));
+
+                if !between_digits {
+                    return Err(self.number_error(InvalidSeparator));
+                }
+            } else {
+                break;
             }
         }
 
-        TokenTag::Number(
-            f64::from(
-                mantissa/10.0_f64.powi(exp)
-            )
-        )
+        Ok(digits)
     }
 
-    fn lex_digit(&mut self) -> f64 {
-        f64::from(self.accept().unwrap().to_digit(10).unwrap())
+    /// Number literal can't be immediately followed by a letter or a digit,
+    /// e.g. `12abc` or `0b102`
+    fn check_number_end(&mut self) -> Result<(), LexicalError> {
+        match self.current() {
+            Some(ch) if self.is_next_identifier_char(ch) => {
+                Err(self.number_error(InvalidDigit))
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Create error for the malformed number literal,
+    /// consuming the rest of it
+    fn number_error(&mut self, tag: LexicalErrorTag) -> LexicalError {
+        while let Some(ch) = self.current() {
+            if !self.is_next_identifier_char(ch) {
+                break;
+            }
+
+            self.accept();
+        }
+
+        LexicalError {
+            token: self.create_token(TokenTag::Error),
+            tag: tag
+        }
     }
 
     fn is_next_identifier_char(&self, ch: char) -> bool {
@@ -312,6 +654,7 @@ impl Lexer {
         ch.is_ascii_alphabetic() || ch == '_'
     }
 
+    /// Keywords are ASCII, so byte and character lengths are the same
     fn match_word(&mut self, word: &'static str) -> bool {
         if self.check_word(word) {
             self.curr += word.len();
@@ -322,8 +665,15 @@ impl Lexer {
         false
     }
 
+    /// Check if the source continues with the `word`,
+    /// which is not a prefix of a longer identifier
     fn check_word(&mut self, word: &'static str) -> bool {
-        &self.src[self.curr..self.curr+word.len()] == String::from(word)
+        let end = self.curr+word.len();
+
+        self.src.get(self.curr..end) == Some(word) && !self.src[end..]
+            .chars()
+            .next()
+            .is_some_and(|ch| self.is_next_identifier_char(ch))
     }
 
     fn match_next(&mut self, ch: char) -> bool {
@@ -340,7 +690,7 @@ impl Lexer {
         let ch = self.current();
 
         if ch.is_some() {
-            self.curr += 1;
+            self.curr += ch.unwrap().len_utf8();
 
             if ch.unwrap() == '\n' {
                 self.line += 1;
@@ -361,14 +711,14 @@ impl Lexer {
 
     #[allow(dead_code)]
     fn next(&self) -> Option<char> {
-        self.src.chars().nth(self.curr+1)
+        self.src[self.curr..].chars().nth(1)
     }
 
     fn prev(&self) -> Option<char> {
-        self.src.chars().nth(self.curr-1)
+        self.src[..self.curr].chars().next_back()
     }
 
     fn current(&self) -> Option<char> {
-        self.src.chars().nth(self.curr)
+        self.src[self.curr..].chars().next()
     }
 }
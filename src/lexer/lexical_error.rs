@@ -12,6 +12,13 @@ pub struct LexicalError {
 #[derive(Debug)]
 pub enum LexicalErrorTag {
     UnknownToken,
+    InvalidEscape,
+    UnterminatedString,
+    MissingDigits,
+    MissingExponent,
+    InvalidDigit,
+    InvalidSeparator,
+    NumberOutOfRange,
 }
 
 impl DescribableError for LexicalError {
@@ -23,6 +30,30 @@ impl DescribableError for LexicalError {
         match self.tag {
             LexicalErrorTag::UnknownToken => {
                 format!("Unknown token")
+            },
+            LexicalErrorTag::InvalidEscape => {
+                format!("Invalid escape sequence `{}`", self.token.get_lexeme())
+            },
+            LexicalErrorTag::UnterminatedString => {
+                format!("Unterminated string")
+            },
+            LexicalErrorTag::MissingDigits => {
+                format!("Number literal `{}` has no digits", self.token.get_lexeme())
+            },
+            LexicalErrorTag::MissingExponent => {
+                format!("Number literal `{}` has no exponent", self.token.get_lexeme())
+            },
+            LexicalErrorTag::InvalidDigit => {
+                format!("Invalid digit in number literal `{}`", self.token.get_lexeme())
+            },
+            LexicalErrorTag::InvalidSeparator => {
+                format!(
+                    "Digit separator must be placed between digits in `{}`",
+                    self.token.get_lexeme()
+                )
+            },
+            LexicalErrorTag::NumberOutOfRange => {
+                format!("Number literal `{}` is too large", self.token.get_lexeme())
             }
         }
     }
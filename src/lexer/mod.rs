@@ -4,3 +4,4 @@ mod lexical_error;
 
 pub mod token;
 pub use lexer::Lexer;
+pub use lexical_error::LexicalError;
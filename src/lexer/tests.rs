@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::lexer::{Lexer, token::TokenTag::{self, *}};
+    use crate::lexer::{
+        Lexer,
+        token::TokenTag::{self, *},
+        lexical_error::LexicalErrorTag
+    };
 
     #[test]
     fn mixed_tokens() {
@@ -30,7 +34,7 @@ mod tests {
             Loop, Identifier("a".into()), Greater, Identifier("b".into()),
             LeftCurly, Print, String("some string".into()), Semicolon,
             Identifier("a".into()), Equal, Identifier("a".into()), Minus,
-            Number(1.0), Semicolon, RightCurly, EndOfFile
+            Integer(1), Semicolon, RightCurly, EndOfFile
         ];
 
         assert_eq!(tokens, expected);
@@ -63,7 +67,8 @@ mod tests {
     #[test]
     fn keyword_tokens() {
         let mut lexer = Lexer::from_string("
-        else if true false func print let loop return
+        else if true false null func print let loop repeat until break
+        continue return and or not class match throw try catch finally import
         ".into());
 
         let tokens: Vec<TokenTag> = lexer
@@ -74,7 +79,39 @@ mod tests {
             .collect();
 
         let expected = vec![
-            Else, If, True, False, Func, Print, Let, Loop, Return,
+            Else, If, True, False, Null, Func, Print, Let, Loop, Repeat,
+            Until, Break, Continue, Return, And, Or, Not, Class, Match,
+            Throw, Try, Catch, Finally, Import, EndOfFile
+        ];
+
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn identifiers_starting_with_keywords() {
+        let mut lexer = Lexer::from_string("
+        order android nothing iffy letter classic matches trying truth
+        important
+        ".into());
+
+        let tokens: Vec<TokenTag> = lexer
+            .lex()
+            .unwrap()
+            .iter()
+            .map(|token| token.tag.clone())
+            .collect();
+
+        let expected = vec![
+            Identifier("order".into()),
+            Identifier("android".into()),
+            Identifier("nothing".into()),
+            Identifier("iffy".into()),
+            Identifier("letter".into()),
+            Identifier("classic".into()),
+            Identifier("matches".into()),
+            Identifier("trying".into()),
+            Identifier("truth".into()),
+            Identifier("important".into()),
             EndOfFile
         ];
 
@@ -84,7 +121,7 @@ mod tests {
     #[test]
     fn two_character_tokens() {
         let mut lexer = Lexer::from_string("
-        >= <= += -= *= /= != == -> <-
+        >= <= += -= *= /= != == -> <- // << >> .. ..= ...
         ".into());
 
         let tokens: Vec<TokenTag> = lexer
@@ -97,7 +134,8 @@ mod tests {
         let expected = vec![
             GreaterEqual, LessEqual, PlusEqual, MinusEqual,
             StarEqual, SlashEqual, BangEqual, EqualEqual,
-            ArrowRight, ArrowLeft, EndOfFile
+            ArrowRight, ArrowLeft, SlashSlash, LessLess, GreaterGreater,
+            DotDot, DotDotEqual, DotDotDot, EndOfFile
         ];
 
         assert_eq!(tokens, expected);
@@ -106,7 +144,7 @@ mod tests {
     #[test]
     fn one_character_tokens() {
         let mut lexer = Lexer::from_string("
-        */+- = (){}.,
+        */+- = (){}[].,:%&|~
         ;^!<>
         ".into());
 
@@ -120,10 +158,154 @@ mod tests {
         let expected = vec![
             Star, Slash, Plus, Minus, Equal,
             LeftParen, RightParen, LeftCurly, RightCurly,
-            Dot, Comma, Semicolon, Circ, Bang, Less, Greater,
+            LeftSquare, RightSquare, Dot, Comma, Colon, Percent,
+            Ampersand, Pipe, Tilde, Semicolon, Circ, Bang, Less, Greater,
+            EndOfFile
+        ];
+
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let mut lexer = Lexer::from_string(r#"
+        "a\nb\t\"c\"\\" "\u{41}\u{1F600}" "привет"
+        "#.into());
+
+        let tokens: Vec<TokenTag> = lexer
+            .lex()
+            .unwrap()
+            .iter()
+            .map(|token| token.tag.clone())
+            .collect();
+
+        let expected = vec![
+            String("a\nb\t\"c\"\\".into()),
+            String("A😀".into()),
+            String("привет".into()),
+            EndOfFile
+        ];
+
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn invalid_escape() {
+        let mut lexer = Lexer::from_string(r#"
+        "ok" "bad \q" "\u{110000}" ok
+        "#.into());
+
+        let errors = lexer.lex().unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0].tag, LexicalErrorTag::InvalidEscape));
+        assert_eq!(errors[0].token.get_lexeme(), "\\q");
+        assert!(matches!(errors[1].tag, LexicalErrorTag::InvalidEscape));
+        assert_eq!(errors[1].token.get_lexeme(), "\\u{110000}");
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let mut lexer = Lexer::from_string("
+        print \"never ends;
+        ".into());
+
+        let errors = lexer.lex().unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].tag, LexicalErrorTag::UnterminatedString));
+        assert_eq!(errors[0].token.info.line, 2);
+        assert_eq!(errors[0].token.info.col, 15);
+    }
+
+    #[test]
+    fn interpolated_strings() {
+        let mut lexer = Lexer::from_string(r#"
+        "a {b} c {{"k": "{d}"}} e" "\{f\}"
+        "#.into());
+
+        let tokens: Vec<TokenTag> = lexer
+            .lex()
+            .unwrap()
+            .iter()
+            .map(|token| token.tag.clone())
+            .collect();
+
+        let expected = vec![
+            StringStart("a ".into()),
+            Identifier("b".into()),
+            StringMiddle(" c ".into()),
+            LeftCurly,
+            String("k".into()),
+            Colon,
+            StringStart("".into()),
+            Identifier("d".into()),
+            StringEnd("".into()),
+            RightCurly,
+            StringEnd(" e".into()),
+            String("{f}".into()),
+            EndOfFile
+        ];
+
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn number_literals() {
+        let mut lexer = Lexer::from_string("
+        1_000_000 0xFF 0b1010 0o17 9223372036854775807 3.0 1e-9 2.5E3 0.1
+        12345678901234567890.0 1.foo -9223372036854775808
+        ".into());
+
+        let tokens: Vec<TokenTag> = lexer
+            .lex()
+            .unwrap()
+            .iter()
+            .map(|token| token.tag.clone())
+            .collect();
+
+        let expected = vec![
+            Integer(1_000_000),
+            Integer(255),
+            Integer(10),
+            Integer(15),
+            Integer(i64::MAX),
+            Number(3.0),
+            Number(1e-9),
+            Number(2500.0),
+            Number(0.1),
+            Number(12345678901234567890.0),
+            Integer(1), Dot, Identifier("foo".into()),
+            Minus, Integer(i64::MIN),
             EndOfFile
         ];
 
         assert_eq!(tokens, expected);
     }
+
+    #[test]
+    fn malformed_number_literals() {
+        let mut lexer = Lexer::from_string("
+        0x 1e 1e+ 0b102 12abc 1__0 1_ 0x8000_0000_0000_0001 9223372036854775809
+        ".into());
+
+        let errors: Vec<(std::string::String, LexicalErrorTag)> = lexer
+            .lex()
+            .unwrap_err()
+            .into_iter()
+            .map(|error| (error.token.get_lexeme(), error.tag))
+            .collect();
+
+        assert_eq!(errors.len(), 9);
+        assert!(matches!(errors[0].1, LexicalErrorTag::MissingDigits));
+        assert!(matches!(errors[1].1, LexicalErrorTag::MissingExponent));
+        assert!(matches!(errors[2].1, LexicalErrorTag::MissingExponent));
+        assert!(matches!(errors[3].1, LexicalErrorTag::InvalidDigit));
+        assert_eq!(errors[4].0, "12abc");
+        assert!(matches!(errors[4].1, LexicalErrorTag::InvalidDigit));
+        assert!(matches!(errors[5].1, LexicalErrorTag::InvalidSeparator));
+        assert!(matches!(errors[6].1, LexicalErrorTag::InvalidSeparator));
+        assert!(matches!(errors[7].1, LexicalErrorTag::NumberOutOfRange));
+        assert!(matches!(errors[8].1, LexicalErrorTag::NumberOutOfRange));
+    }
 }
@@ -21,9 +21,14 @@ pub enum TokenTag {
     Minus,
     Star,
     Slash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Tilde,
     Bang,
     Dot,
     Comma,
+    Colon,
     Circ,
     Semicolon,
     Less,
@@ -33,20 +38,30 @@ pub enum TokenTag {
     RightParen,
     LeftCurly,
     RightCurly,
+    LeftSquare,
+    RightSquare,
     // Two character long
     PlusEqual,
     MinusEqual,
     StarEqual,
     SlashEqual,
+    SlashSlash,
+    LessLess,
+    GreaterGreater,
     ArrowLeft,
     ArrowRight,
     EqualEqual,
     BangEqual,
     LessEqual,
     GreaterEqual,
+    DotDot,
+    // Three character long
+    DotDotEqual,
+    DotDotDot,
     // Keywords
     False,
     True,
+    Null,
     Func,
     Print,
     Let,
@@ -54,10 +69,31 @@ pub enum TokenTag {
     Else,
     Loop,
     Repeat,
+    Until,
+    Break,
+    Continue,
     Return,
+    And,
+    Or,
+    Not,
+    Class,
+    Match,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Import,
     // Other
+    Integer(i64),
+    /// Floating point number
     Number(f64),
     String(String),
+    /// Part of the interpolated string before the first expression
+    StringStart(String),
+    /// Part of the interpolated string between two expressions
+    StringMiddle(String),
+    /// Part of the interpolated string after the last expression
+    StringEnd(String),
     Identifier(String),
     Error,
     EndOfFile
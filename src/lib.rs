@@ -0,0 +1,18 @@
+//! Novis is a simple, tree-walking interpreter.
+//!
+//! `Engine` runs the scripts inside the host program,
+//! `Repl` is the dialog mode of the command line client.
+
+mod errors;
+mod lexer;
+mod parser;
+mod interpreter;
+mod engine;
+mod repl;
+
+pub use engine::{Engine, Error};
+pub use errors::DescribableError;
+pub use interpreter::{Value, RuntimeError};
+pub use lexer::LexicalError;
+pub use parser::ParseError;
+pub use repl::Repl;
@@ -1,8 +1,9 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, rc::Rc};
 
 use crate::{
     lexer::token::Token,
-    errors::Span
+    errors::Span,
+    parser::ast::statement::{Group, Cond, Match, Params}
 };
 
 #[derive(Debug, Clone)]
@@ -34,7 +35,24 @@ impl From<Expression> for Span {
 pub enum ExpressionNode {
     Primary(PrimaryNode),
     Unary(UnaryNode),
-    Binary(BinaryNode)
+    Binary(BinaryNode),
+    /// Group used as an expression, its value is the value
+    /// of the last statement
+    /// ```text
+    /// { <stmts> }
+    /// ```
+    Block(Rc<Group>),
+    /// Conditional expression, its value is the value of the chosen block
+    /// or `null` if there is no such
+    /// ```text
+    /// if <condition> <if_block> else <else_block>
+    /// ```
+    If(Rc<Cond>),
+    /// Match expression, its value is the value of the matching arm
+    /// ```text
+    /// match <scrutinee> { <arms> }
+    /// ```
+    Match(Rc<Match>)
 }
 
 impl From<ExpressionNode> for Span {
@@ -42,7 +60,22 @@ impl From<ExpressionNode> for Span {
         match node {
             ExpressionNode::Primary(primary) => Span::from(primary),
             ExpressionNode::Unary(node) => Span::from(node),
-            ExpressionNode::Binary(node) => Span::from(node)
+            ExpressionNode::Binary(node) => Span::from(node),
+            ExpressionNode::Block(group) => Span {
+                start: group.lcurly.clone(),
+                end: group.rcurly.clone()
+            },
+            ExpressionNode::If(cond) => Span {
+                start: cond.keyword.clone(),
+                end: match &cond.else_block {
+                    Some(block) => block.rcurly.clone(),
+                    None => cond.if_block.rcurly.clone()
+                }
+            },
+            ExpressionNode::Match(stmt) => Span {
+                start: stmt.keyword.clone(),
+                end: stmt.rcurly.clone()
+            }
         }
     }
 }
@@ -87,13 +120,80 @@ pub enum PrimaryNode {
         expr: Box<Expression>
     },
     Identifier(Token),
+    /// List literal
+    /// ```text
+    /// [<items>]
+    /// ```
+    List {
+        lsquare: Token,
+        items: Vec<Box<Expression>>,
+        rsquare: Token
+    },
+    /// Interpolated string, i.e. concatenation of the string parts
+    /// and the values of expressions between them
+    /// ```text
+    /// "<text>{<expr>}<text>{<expr>}<text>"
+    /// ^^^^^^^^ - start        ^^^^^^^ - last part
+    /// ```
+    Interpolation {
+        start: Token,
+        /// Expressions with the string parts following them
+        parts: Vec<(Box<Expression>, Token)>
+    },
+    /// Map literal
+    /// ```text
+    /// {<key>: <value>, ...}
+    /// ```
+    Map {
+        lcurly: Token,
+        entries: Vec<(Box<Expression>, Box<Expression>)>,
+        rcurly: Token
+    },
+    /// Property access
+    /// ```text
+    /// <object>.<name>
+    /// ```
+    Property {
+        object: Box<Expression>,
+        name: Token
+    },
+    /// Indexing
+    /// ```text
+    /// <object>[<index>]
+    /// ```
+    Index {
+        object: Box<Expression>,
+        index: Box<Expression>,
+        rsquare: Token
+    },
     Call {
-        name: Token,
-        args: Vec<Box<Expression>>,
+        callee: Box<Expression>,
+        args: Vec<Arg>,
         rparen: Token
+    },
+    /// Anonymous function
+    /// ```text
+    /// func <params> -> <body>
+    /// ^^^^ - keyword
+    /// ```
+    Lambda {
+        keyword: Token,
+        params: Rc<Params>,
+        body: Rc<Group>
     }
 }
 
+/// Argument of the call, named ones are passed to the parameter
+/// with the same name regardless of their position
+/// ```text
+/// <name>: <expr>
+/// ```
+#[derive(Debug, Clone)]
+pub struct Arg {
+    pub name: Option<Token>,
+    pub expr: Box<Expression>
+}
+
 impl From<PrimaryNode> for Span {
     fn from(node: PrimaryNode) -> Span {
         match node {
@@ -113,14 +213,60 @@ impl From<PrimaryNode> for Span {
                 start: lparen.clone(),
                 end: rparen.clone()
             },
+            PrimaryNode::List {
+                lsquare,
+                items: _,
+                rsquare
+            } => Span {
+                start: lsquare.clone(),
+                end: rsquare.clone()
+            },
+            PrimaryNode::Interpolation {
+                start,
+                parts
+            } => Span {
+                start: start.clone(),
+                end: parts.last().unwrap().1.clone()
+            },
+            PrimaryNode::Map {
+                lcurly,
+                entries: _,
+                rcurly
+            } => Span {
+                start: lcurly.clone(),
+                end: rcurly.clone()
+            },
+            PrimaryNode::Property {
+                object,
+                name
+            } => Span {
+                start: Span::from(object.as_ref().clone()).start,
+                end: name.clone()
+            },
+            PrimaryNode::Index {
+                object,
+                index: _,
+                rsquare
+            } => Span {
+                start: Span::from(object.get_node().clone()).start,
+                end: rsquare.clone()
+            },
             PrimaryNode::Call {
-                name,
+                callee,
                 args: _,
                 rparen
             } => Span {
-                start: name.clone(),
+                start: Span::from(callee.get_node().clone()).start,
                 end: rparen.clone()
             },
+            PrimaryNode::Lambda {
+                keyword,
+                params: _,
+                body
+            } => Span {
+                start: keyword.clone(),
+                end: body.rcurly.clone()
+            },
         }
     }
 }
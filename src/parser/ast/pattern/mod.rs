@@ -0,0 +1,3 @@
+mod pattern;
+
+pub use pattern::*;
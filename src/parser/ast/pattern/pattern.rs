@@ -0,0 +1,109 @@
+use crate::{
+    lexer::token::Token,
+    errors::Span
+};
+
+/// Pattern of the `match` arm representation.
+#[derive(Debug)]
+pub enum Pattern {
+    /// Matches any value
+    /// ```text
+    /// _
+    /// ```
+    Wildcard(Token),
+    /// Matches values equal to the literal
+    /// ```text
+    /// -1   "text"   true   null
+    /// ```
+    Literal(Literal),
+    /// Matches numbers from `start` up to `end`,
+    /// `end` is included only for `..=`
+    /// ```text
+    /// <start>..<end>   <start>..=<end>
+    ///        ^^ - op          ^^^ - op
+    /// ```
+    Range {
+        start: Literal,
+        op: Token,
+        end: Literal
+    },
+    /// Matches any value and binds it to the name
+    /// ```text
+    /// <name>
+    /// ```
+    Binding(Token),
+    /// Matches lists of the same length, whose items match the patterns
+    /// ```text
+    /// [ <items> ]
+    /// ```
+    List {
+        lsquare: Token,
+        items: Vec<Pattern>,
+        rsquare: Token
+    },
+    /// Matches maps having all the keys, or objects having all the fields.
+    /// If the class is given, only its instances are matched.
+    /// ```text
+    /// <class>? { <fields> }
+    /// ```
+    Record {
+        class: Option<Token>,
+        lcurly: Token,
+        fields: Vec<FieldPattern>,
+        rcurly: Token
+    }
+}
+
+impl From<&Pattern> for Span {
+    fn from(pattern: &Pattern) -> Self {
+        match pattern {
+            Pattern::Wildcard(token) | Pattern::Binding(token) => {
+                Span::from(token.clone())
+            },
+            Pattern::Literal(literal) => literal.into(),
+            Pattern::Range { start, op: _, end } => Self {
+                start: Span::from(start).start,
+                end: end.token.clone()
+            },
+            Pattern::List { lsquare, items: _, rsquare } => Self {
+                start: lsquare.clone(),
+                end: rsquare.clone()
+            },
+            Pattern::Record { class, lcurly, fields: _, rcurly } => Self {
+                start: class.as_ref().unwrap_or(lcurly).clone(),
+                end: rcurly.clone()
+            }
+        }
+    }
+}
+
+/// Literal of the pattern, numbers may be negated
+/// ```text
+/// -     <token>
+/// ^ - minus
+/// ```
+#[derive(Debug)]
+pub struct Literal {
+    pub minus: Option<Token>,
+    pub token: Token
+}
+
+impl From<&Literal> for Span {
+    fn from(literal: &Literal) -> Self {
+        Self {
+            start: literal.minus.as_ref().unwrap_or(&literal.token).clone(),
+            end: literal.token.clone()
+        }
+    }
+}
+
+/// Field of the record pattern.
+/// Without the pattern the value is bound to the field name.
+/// ```text
+/// <key>: <pattern>   <key>
+/// ```
+#[derive(Debug)]
+pub struct FieldPattern {
+    pub key: Token,
+    pub pattern: Option<Pattern>
+}
@@ -1,7 +1,10 @@
 use std::rc::Rc;
 
 use crate::{
-    parser::ast::expression::Expression,
+    parser::ast::{
+        expression::Expression,
+        pattern::Pattern
+    },
     lexer::token::Token, errors::Span
 };
 
@@ -11,12 +14,21 @@ pub enum Statement {
     Group(Group),
     Expr(ExprStatment),
     Retrun(Return),
+    Break(Break),
+    Continue(Continue),
+    Throw(Throw),
+    Try(Try),
     Print(Print),
     Let(Let),
     Func(Func),
     Cond(Cond),
+    Match(Match),
     Loop(Loop),
+    Repeat(Repeat),
+    RepeatUntil(RepeatUntil),
     Assign(Assignment),
+    Class(Class),
+    Import(Import),
 }
 
 /// Group statement representation.
@@ -75,6 +87,97 @@ impl From<Return> for Span {
     }
 }
 
+/// Break statement representation.
+/// ```text
+/// break
+/// ^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Break {
+    pub keyword: Token,
+}
+
+impl From<Break> for Span {
+    fn from(stmt: Break) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Continue statement representation.
+/// ```text
+/// continue
+/// ^^^^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Continue {
+    pub keyword: Token,
+}
+
+impl From<Continue> for Span {
+    fn from(stmt: Continue) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Throw statement representation.
+/// ```text
+/// throw <expr>
+/// ^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Throw {
+    pub keyword: Token,
+    pub expr: Box<Expression>,
+}
+
+impl From<Throw> for Span {
+    fn from(stmt: Throw) -> Self {
+        Self {
+            start: stmt.keyword,
+            end: Span::from(stmt.expr.as_ref().clone()).end
+        }
+    }
+}
+
+/// Try statement representation.
+/// At least one of `catch` and `finally` is present.
+/// ```text
+/// try <body> <catch>? (finally <finally>)?
+/// ^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Try {
+    pub keyword: Token,
+    pub body: Box<Group>,
+    pub catch: Option<Catch>,
+    pub finally: Option<Box<Group>>
+}
+
+impl From<Try> for Span {
+    fn from(stmt: Try) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Catch clause of the try statement.
+/// Caught error is bound to the `name` in the body.
+/// ```text
+/// catch <name> <body>
+/// ^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Catch {
+    pub keyword: Token,
+    pub name: Token,
+    pub body: Box<Group>
+}
+
+impl From<Catch> for Span {
+    fn from(clause: Catch) -> Self {
+        Span::from(clause.keyword)
+    }
+}
+
 /// Variable declaration statement representation.
 /// ```text
 /// let <name>     <- <expr>
@@ -99,20 +202,21 @@ impl From<Let> for Span {
 
 /// Assignment statement representation.
 /// ```text
-/// <name> <- <expr>
-///        ^^ - operator
+/// <target> <- <expr>
+///          ^^ - operator (`<-`, `+=`, `-=`, `*=` or `/=`)
 /// ```
+/// Target is either an identifier or an indexing expression.
 #[derive(Debug)]
 pub struct Assignment {
     pub operator: Token,
-    pub name: Token,
+    pub target: Box<Expression>,
     pub expr: Box<Expression>
 }
 
 impl From<Assignment> for Span {
     fn from(stmt: Assignment) -> Self {
         Self {
-            start: stmt.name,
+            start: Span::from(stmt.target.as_ref().clone()).start,
             end: Span::from(stmt.expr.as_ref().clone()).end
         }
     }
@@ -127,7 +231,7 @@ impl From<Assignment> for Span {
 pub struct Func {
     pub keyword: Token,
     pub name: Token,
-    pub params: Vec<Token>,
+    pub params: Rc<Params>,
     pub body: Rc<Group>
 }
 
@@ -137,6 +241,78 @@ impl From<Func> for Span {
     }
 }
 
+/// Parameters of the function, the rest one collects
+/// extra positional arguments into a list
+/// ```text
+/// (<params>, ...<rest>)
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    pub params: Vec<Param>,
+    pub rest: Option<Token>
+}
+
+impl Params {
+    /// Number of the parameters without default values
+    pub fn required(&self) -> usize {
+        self.params.iter().filter(|param| param.default.is_none()).count()
+    }
+}
+
+/// Function parameter, the default value is evaluated
+/// on every call the argument is not passed to
+/// ```text
+/// <name> <- <default>
+/// ```
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Token,
+    pub default: Option<Box<Expression>>
+}
+
+/// Class declaration representation
+/// ```text
+/// class <name> { <fields> <methods> }
+/// ^^^^^ - keyword
+/// ```
+/// Fields are declared with `let`, methods with `func`.
+#[derive(Debug)]
+pub struct Class {
+    pub keyword: Token,
+    pub name: Token,
+    /// Field initializers are evaluated for every new instance
+    pub fields: Rc<Vec<Let>>,
+    pub methods: Vec<Func>
+}
+
+impl From<Class> for Span {
+    fn from(stmt: Class) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Import statement representation.
+/// Module is either a name, which is looked up as `<name>.novis`,
+/// or a path in the string literal.
+/// ```text
+/// import <module>
+/// ^^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Import {
+    pub keyword: Token,
+    pub module: Token
+}
+
+impl From<Import> for Span {
+    fn from(stmt: Import) -> Self {
+        Self {
+            start: stmt.keyword,
+            end: stmt.module
+        }
+    }
+}
+
 /// Loop statement representation
 /// ```text
 /// loop <condition> <body>
@@ -155,6 +331,42 @@ impl From<Loop> for Span {
     }
 }
 
+/// Repeat statement representation
+/// ```text
+/// repeat <count> <body>
+/// ^^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct Repeat {
+    pub keyword: Token,
+    pub count: Box<Expression>,
+    pub body: Box<Group>
+}
+
+impl From<Repeat> for Span {
+    fn from(stmt: Repeat) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Repeat-until statement representation
+/// ```text
+/// repeat <body> until <condition>
+/// ^^^^^^ - keyword
+/// ```
+#[derive(Debug)]
+pub struct RepeatUntil {
+    pub keyword: Token,
+    pub body: Box<Group>,
+    pub condition: Box<Expression>
+}
+
+impl From<RepeatUntil> for Span {
+    fn from(stmt: RepeatUntil) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
 /// If statement representation
 /// ```text
 /// if <condition> <if_block> <else_block>?
@@ -174,6 +386,38 @@ impl From<Cond> for Span {
     }
 }
 
+/// Match statement representation.
+/// Arms are tried in order, the value of the statement is the value
+/// of the first arm whose pattern matches and guard holds.
+/// ```text
+/// match <scrutinee> { <arms> }
+/// ^^^^^ - keyword           ^ - rcurly
+/// ```
+#[derive(Debug)]
+pub struct Match {
+    pub keyword: Token,
+    pub scrutinee: Box<Expression>,
+    pub arms: Vec<Arm>,
+    pub rcurly: Token
+}
+
+impl From<Match> for Span {
+    fn from(stmt: Match) -> Self {
+        Span::from(stmt.keyword)
+    }
+}
+
+/// Arm of the match statement
+/// ```text
+/// <pattern> if <guard> -> <body>
+/// ```
+#[derive(Debug)]
+pub struct Arm {
+    pub pattern: Pattern,
+    pub guard: Option<Box<Expression>>,
+    pub body: Box<Expression>
+}
+
 /// Print statement representation.
 /// This is very simple statement that allows you
 /// to output values in ouput stream.
@@ -1,11 +1,24 @@
-use crate::lexer::token::TokenTag::{*, self};
+use std::rc::Rc;
+
+use crate::{
+    lexer::token::{TokenTag::{*, self}, Token, Lexeme},
+    errors::DebugInfo
+};
 use super::{
+    statement::{
+        parse_params,
+        func_body,
+        cond,
+        group,
+        r#match
+    },
     ast::expression::{
         Expression,
         PrimaryNode,
         UnaryNode,
         BinaryNode,
         ExpressionNode,
+        Arg,
     },
     parse_error::{
         ParseErrorTag::*,
@@ -19,7 +32,51 @@ use super::{
 /// # Arguments
 /// * `tokens` - Stream of the tokens
 pub fn expression(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
-    equality(tokens)
+    or(tokens)
+}
+
+/// # Rule
+/// ```ebnf
+/// or = and ('or' and)*;
+/// ```
+fn or(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = and(tokens);
+
+    while tokens.match_next(&[Or]) {
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: and(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node))
+    };
+
+    expr
+}
+
+/// # Rule
+/// ```ebnf
+/// and = equality ('and' equality)*;
+/// ```
+fn and(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = equality(tokens);
+
+    while tokens.match_next(&[And]) {
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: equality(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node))
+    };
+
+    expr
 }
 
 /// # Rule
@@ -46,15 +103,104 @@ fn equality(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
 
 /// # Rule
 /// ```ebnf
-/// comparison = term (('<' | '>' | '<=' | '>=') term)*;
+/// comparison = bit_or (('<' | '>' | '<=' | '>=') bit_or)*;
 /// ```
 fn comparison(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
-    let mut expr = term(tokens);
+    let mut expr = bit_or(tokens);
 
     while tokens.match_next(
         &[Less, Greater, LessEqual, GreaterEqual]
     ) {
 
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: bit_or(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node));
+    }
+
+    expr
+}
+
+/// # Rule
+/// ```ebnf
+/// bit_or = bit_xor ('|' bit_xor)*;
+/// ```
+fn bit_or(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = bit_xor(tokens);
+
+    while tokens.match_next(&[Pipe]) {
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: bit_xor(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node));
+    }
+
+    expr
+}
+
+/// # Rule
+/// Exclusive or is spelled as `~`, because `^` is the exponent:
+/// ```ebnf
+/// bit_xor = bit_and ('~' bit_and)*;
+/// ```
+fn bit_xor(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = bit_and(tokens);
+
+    while tokens.match_next(&[Tilde]) {
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: bit_and(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node));
+    }
+
+    expr
+}
+
+/// # Rule
+/// ```ebnf
+/// bit_and = shift ('&' shift)*;
+/// ```
+fn bit_and(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = shift(tokens);
+
+    while tokens.match_next(&[Ampersand]) {
+        let node = ExpressionNode::Binary(
+            BinaryNode {
+                op: tokens.prev().clone(),
+                left: expr?,
+                right: shift(tokens)?,
+            }
+        );
+
+        expr = Ok(Expression::create(node));
+    }
+
+    expr
+}
+
+/// # Rule
+/// ```ebnf
+/// shift = term (('<<' | '>>') term)*;
+/// ```
+fn shift(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = term(tokens);
+
+    while tokens.match_next(&[LessLess, GreaterGreater]) {
         let node = ExpressionNode::Binary(
             BinaryNode {
                 op: tokens.prev().clone(),
@@ -94,12 +240,12 @@ fn term(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
 
 /// # Rule
 /// ```ebnf
-/// factor = unary (('*' | '/') unary)*;
+/// factor = unary (('*' | '/' | '//' | '%') unary)*;
 /// ```
 fn factor(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
     let mut expr = exponent(tokens);
 
-    while tokens.match_next(&[Star, Slash]) {
+    while tokens.match_next(&[Star, Slash, SlashSlash, Percent]) {
         let node = ExpressionNode::Binary (
             BinaryNode {
                 op: tokens.prev().clone(),
@@ -137,36 +283,144 @@ fn exponent(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
 
 /// # Rule
 /// ```ebnf
-/// unary = '-' primary;
+/// unary = ('-' | '!' | 'not' | '~') unary | call;
 /// ```
 fn unary(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
-    if tokens.match_next(&[Minus]) {
+    if tokens.match_next(&[Minus, Bang, Not, Tilde]) {
+        if let Some(literal) = negative_integer(tokens) {
+            let node = ExpressionNode::Primary(PrimaryNode::Literal(literal));
+            return Ok(Expression::create(node));
+        }
+
         let node = ExpressionNode::Unary(
             UnaryNode {
                 op: tokens.prev().clone(),
-                left: primary(tokens)?,
+                left: unary(tokens)?,
             }
         );
 
         return Ok(Expression::create(node));
     }
 
-    primary(tokens)
+    call(tokens)
+}
+
+/// Negated integer literal is folded into a single literal,
+/// otherwise `-9223372036854775808` would overflow before negation.
+/// The minus is expected to be consumed already.
+fn negative_integer(tokens: &mut TokenStream) -> Option<Token> {
+    let minus = tokens.prev().clone();
+    let literal = tokens.current();
+
+    let Integer(n) = literal.tag else {
+        return None;
+    };
+
+    // Literal must be the whole operand of the minus
+    let is_operand = !matches!(tokens.lookahead(1).tag, LeftParen | LeftSquare | Dot);
+
+    if minus.tag != Minus || minus.info.line != literal.info.line || !is_operand {
+        return None;
+    }
+
+    let literal = tokens.accept().clone();
+
+    Some(Token {
+        tag: Integer(n.wrapping_neg()),
+        lexeme: Lexeme {
+            start: minus.lexeme.start,
+            end: literal.lexeme.end
+        },
+        info: DebugInfo {
+            len: literal.lexeme.end - minus.lexeme.start,
+            ..minus.info
+        }
+    })
 }
 
 /// # Rule
+/// Function calls, indexing and property access match following grammary:
 /// ```ebnf
-/// primary = literal | identifier | '(' expression ')';
+/// call = primary ('(' args ')' | '[' expression ']' | '.' identifier)*;
+/// ```
+fn call(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    let mut expr = primary(tokens)?;
+
+    loop {
+        let node = match tokens.current().tag {
+            LeftParen => PrimaryNode::Call {
+                callee: expr,
+                args: parse_args(tokens)?,
+                rparen: tokens.prev().clone()
+            },
+            LeftSquare => {
+                tokens.accept();
+
+                PrimaryNode::Index {
+                    object: expr,
+                    index: expression(tokens)?,
+                    rsquare: tokens.require(&[RightSquare])?.clone()
+                }
+            },
+            Dot => {
+                tokens.accept();
+
+                let name = match tokens.current().tag {
+                    Identifier(_) => tokens.accept().clone(),
+                    _ => return Err(ParseError {
+                        token: tokens.current().clone(),
+                        tag: ExpectedIdentifier
+                    })
+                };
+
+                PrimaryNode::Property {
+                    object: expr,
+                    name: name
+                }
+            },
+            _ => break Ok(expr)
+        };
+
+        expr = Expression::create(ExpressionNode::Primary(node));
+    }
+}
+
+/// # Rule
+/// ```ebnf
+/// primary = literal | identifier | interpolation | list | map | lambda
+///         | cond | match | group | '(' expression ')';
+/// literal = integer | number | string | 'true' | 'false' | 'null';
 /// ```
 fn primary(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
+    if tokens.match_next(&[If]) {
+        let node = ExpressionNode::If(Rc::new(cond(tokens)?));
+        return Ok(Expression::create(node));
+    }
+
+    if tokens.match_next(&[Match]) {
+        let node = ExpressionNode::Match(Rc::new(r#match(tokens)?));
+        return Ok(Expression::create(node));
+    }
+
+    if tokens.current().tag == LeftCurly && !is_map(tokens) {
+        let node = ExpressionNode::Block(Rc::new(group(tokens)?));
+        return Ok(Expression::create(node));
+    }
+
     let node = ExpressionNode::Primary(match &tokens.accept().tag {
-        Number(_) | String(_) => PrimaryNode::Literal(tokens.prev().clone()),
-        Identifier(_) => {
-            match tokens.current().tag {
-                TokenTag::LeftParen => call(tokens)?,
-                _ => PrimaryNode::Identifier(tokens.prev().clone())
-            }
+        // Only the negated literal may be that large
+        Integer(i64::MIN) => return Err(ParseError {
+            token: tokens.prev().clone(),
+            tag: IntegerOutOfRange
+        }),
+        Integer(_) | Number(_) | String(_) | True | False | Null => {
+            PrimaryNode::Literal(tokens.prev().clone())
         },
+        Identifier(_) => PrimaryNode::Identifier(tokens.prev().clone()),
+        StringStart(_) => interpolation(tokens)?,
+        Func => lambda(tokens)?,
+        LeftSquare => list(tokens)?,
+        LeftCurly => map(tokens)?,
         LeftParen => {
             let lparen = tokens.prev().clone();
             let expr = expression(tokens)?;
@@ -188,44 +442,189 @@ fn primary(tokens: &mut TokenStream) -> Result<Box<Expression>, ParseError> {
 }
 
 /// # Rule
-/// Function call matches following grammary:
+/// Interpolated string matches following grammary:
 /// ```ebnf
-/// call = identifier '(' args ')';
+/// interpolation = string_start expression
+///                 (string_middle expression)* string_end;
 /// ```
-fn call(
+/// String parts are produced by the lexer.
+fn interpolation(
     tokens: &mut TokenStream,
 ) -> Result<PrimaryNode, ParseError> {
-    let identifier = tokens.prev().clone();
-    Ok(PrimaryNode::Call {
-        name: identifier,
-        args: parse_args(tokens)?,
-        rparen: tokens.prev().clone()
+    let start = tokens.prev().clone();
+    let mut parts = vec![];
+
+    loop {
+        let expr = expression(tokens)?;
+
+        match tokens.current().tag {
+            StringMiddle(_) => {
+                parts.push((expr, tokens.accept().clone()));
+            },
+            StringEnd(_) => {
+                parts.push((expr, tokens.accept().clone()));
+
+                break Ok(PrimaryNode::Interpolation {
+                    start: start,
+                    parts: parts
+                });
+            },
+            _ => return Err(ParseError {
+                token: tokens.current().clone(),
+                tag: ExpectedInterpolationEnd
+            })
+        }
+    }
+}
+
+/// # Rule
+/// List literal matches following grammary:
+/// ```ebnf
+/// list = '[' (expression (',' expression)*)? ']';
+/// ```
+fn list(
+    tokens: &mut TokenStream,
+) -> Result<PrimaryNode, ParseError> {
+    let lsquare = tokens.prev().clone();
+    let mut items = vec![];
+
+    loop {
+        match tokens.current().tag {
+            TokenTag::RightSquare => {
+                break Ok(PrimaryNode::List {
+                    lsquare: lsquare,
+                    items: items,
+                    rsquare: tokens.accept().clone()
+                });
+            },
+            _ => {
+                items.push(expression(tokens)?);
+
+                if tokens.current().tag != TokenTag::RightSquare {
+                    tokens.require(&[TokenTag::Comma])?;
+                }
+            }
+        };
+    }
+}
+
+/// Curly brace in expression position starts a map if it's followed
+/// by the closing one, or by a literal or a name and a colon,
+/// otherwise it starts a block. Only a few tokens are looked at,
+/// so nested braces don't have to be parsed twice.
+fn is_map(tokens: &TokenStream) -> bool {
+    // Negative number keys, e.g. `{-1: "a"}`
+    let key = match tokens.lookahead(1).tag {
+        Minus => 2,
+        _ => 1
+    };
+
+    match (&tokens.lookahead(key).tag, &tokens.lookahead(key + 1).tag) {
+        (RightCurly, _) => key == 1,
+        (Integer(_) | Number(_), Colon) => true,
+        (String(_) | True | False | Identifier(_), Colon) => key == 1,
+        _ => false
+    }
+}
+
+/// # Rule
+/// Map literal matches following grammary:
+/// ```ebnf
+/// map = '{' (entry (',' entry)*)? '}';
+/// entry = expression ':' expression;
+/// ```
+fn map(
+    tokens: &mut TokenStream,
+) -> Result<PrimaryNode, ParseError> {
+    let lcurly = tokens.prev().clone();
+    let mut entries = vec![];
+
+    loop {
+        match tokens.current().tag {
+            TokenTag::RightCurly => {
+                break Ok(PrimaryNode::Map {
+                    lcurly: lcurly,
+                    entries: entries,
+                    rcurly: tokens.accept().clone()
+                });
+            },
+            _ => {
+                let key = expression(tokens)?;
+                tokens.require(&[TokenTag::Colon])?;
+                entries.push((key, expression(tokens)?));
+
+                if tokens.current().tag != TokenTag::RightCurly {
+                    tokens.require(&[TokenTag::Comma])?;
+                }
+            }
+        };
+    }
+}
+
+/// # Rule
+/// Anonymous function matches following grammary:
+/// ```ebnf
+/// lambda = 'func' params '->' func_body;
+/// ```
+fn lambda(
+    tokens: &mut TokenStream,
+) -> Result<PrimaryNode, ParseError> {
+    let keyword = tokens.prev().clone();
+    let params = parse_params(tokens)?;
+
+    tokens.require(&[TokenTag::ArrowRight])?;
+
+    Ok(PrimaryNode::Lambda {
+        keyword: keyword,
+        params: Rc::new(params),
+        body: Rc::new(func_body(tokens)?)
     })
 }
 
 /// # Rule
 /// Arguments match following grammary:
 /// ```ebnf
-/// args = expression (',' expression)*;
+/// args = '(' (arg (',' arg)*)? ')';
+/// arg = (identifier ':')? expression;
 /// ```
+/// Positional arguments can't follow the named ones.
 fn parse_args(
     tokens: &mut TokenStream,
-) -> Result<Vec<Box<Expression>>, ParseError> {
-    let mut params = vec![];
+) -> Result<Vec<Arg>, ParseError> {
+    let mut args: Vec<Arg> = vec![];
 
-    tokens.require(&[TokenTag::LeftParen])?;
+    tokens.require(&[LeftParen])?;
 
     loop {
         match tokens.current().tag {
-            TokenTag::RightParen => {
+            RightParen => {
                 tokens.accept();
-                break Ok(params);
+                break Ok(args);
             },
             _ => {
-                params.push(expression(tokens)?);
+                let name = match (&tokens.current().tag, &tokens.lookahead(1).tag) {
+                    (Identifier(_), Colon) => {
+                        let name = tokens.accept().clone();
+                        tokens.accept();
+                        Some(name)
+                    },
+                    _ => None
+                };
+
+                if name.is_none() && args.iter().any(|arg| arg.name.is_some()) {
+                    return Err(ParseError {
+                        token: tokens.current().clone(),
+                        tag: PositionalAfterNamed
+                    });
+                }
 
-                if tokens.current().tag != TokenTag::RightParen {
-                    tokens.require(&[TokenTag::Comma])?;
+                args.push(Arg {
+                    name: name,
+                    expr: expression(tokens)?
+                });
+
+                if tokens.current().tag != RightParen {
+                    tokens.require(&[Comma])?;
                 }
             }
         };
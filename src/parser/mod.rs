@@ -1,8 +1,10 @@
 mod parse_error;
 mod expression;
 mod statement;
+mod pattern;
 mod token_stream;
 mod parser;
 
 pub mod ast;
 pub use parser::Parser;
+pub use parse_error::ParseError;
@@ -18,7 +18,15 @@ pub enum ParseErrorTag {
     UnexpectedToken,
     ExpectedToken(TokenTag),
     ExpectedIdentifier,
-    ExpectedExpression
+    ExpectedExpression,
+    InvalidAssignmentTarget,
+    ExpectedInterpolationEnd,
+    ExpectedPattern,
+    InvalidRangeBound,
+    DuplicateBinding,
+    RequiredAfterDefault,
+    PositionalAfterNamed,
+    IntegerOutOfRange
 }
 
 impl Into<String> for ParseErrorTag {
@@ -45,6 +53,46 @@ impl Into<String> for ParseErrorTag {
                     "expected identifier",
                 )
             },
+            Self::InvalidAssignmentTarget => {
+                format!(
+                    "invalid assignment target",
+                )
+            },
+            Self::ExpectedInterpolationEnd => {
+                format!(
+                    "expected `}}` after the interpolated expression",
+                )
+            },
+            Self::ExpectedPattern => {
+                format!(
+                    "expected pattern",
+                )
+            },
+            Self::InvalidRangeBound => {
+                format!(
+                    "range bound must be a number",
+                )
+            },
+            Self::DuplicateBinding => {
+                format!(
+                    "name is already bound in the pattern",
+                )
+            },
+            Self::RequiredAfterDefault => {
+                format!(
+                    "parameter without default value can't follow the ones with it",
+                )
+            },
+            Self::PositionalAfterNamed => {
+                format!(
+                    "positional argument can't follow the named ones",
+                )
+            },
+            Self::IntegerOutOfRange => {
+                format!(
+                    "integer literal is too large",
+                )
+            },
         }
     }
 }
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use crate::{
+    lexer::token::{
+        TokenTag::{self, *},
+        Token
+    },
+    parser::{
+        token_stream::TokenStream,
+        parse_error::{
+            ParseError,
+            ParseErrorTag::*
+        },
+        ast::pattern::{
+            Pattern,
+            Literal,
+            FieldPattern
+        }
+    }
+};
+
+/// Parse the pattern of the match arm.
+///
+/// # Errors
+/// Besides the syntax errors, emits `DuplicateBinding`
+/// if the pattern binds any name more than once.
+pub fn pattern(tokens: &mut TokenStream) -> Result<Pattern, ParseError> {
+    let pattern = subpattern(tokens)?;
+    check_bindings(&pattern, &mut HashSet::new())?;
+
+    Ok(pattern)
+}
+
+/// Report the first name bound by the pattern the second time,
+/// in the source order
+fn check_bindings(pattern: &Pattern, names: &mut HashSet<std::string::String>) -> Result<(), ParseError> {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Literal(_) | Pattern::Range { .. } => Ok(()),
+        Pattern::Binding(name) => bind(name, names),
+        Pattern::List { lsquare: _, items, rsquare: _ } => {
+            items.iter().try_for_each(|item| check_bindings(item, names))
+        },
+        Pattern::Record { class: _, lcurly: _, fields, rcurly: _ } => {
+            for field in fields {
+                match &field.pattern {
+                    Some(pattern) => check_bindings(pattern, names)?,
+                    None => bind(&field.key, names)?
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn bind(name: &Token, names: &mut HashSet<std::string::String>) -> Result<(), ParseError> {
+    if names.insert(name.get_lexeme()) {
+        return Ok(());
+    }
+
+    Err(ParseError {
+        token: name.clone(),
+        tag: DuplicateBinding
+    })
+}
+
+/// # Rule
+/// Pattern of the match arm matches following grammary:
+/// ```ebnf
+/// pattern = '_' | identifier | literal | range | list | record;
+/// range = number ('..' | '..=') number;
+/// number = '-'? (integer | float);
+/// literal = number | string | 'true' | 'false' | 'null';
+/// ```
+fn subpattern(tokens: &mut TokenStream) -> Result<Pattern, ParseError> {
+    match &tokens.current().tag {
+        Identifier(name) if name == "_" => {
+            Ok(Pattern::Wildcard(tokens.accept().clone()))
+        },
+        Identifier(_) if tokens.lookahead(1).tag == LeftCurly => record(tokens),
+        Identifier(_) => Ok(Pattern::Binding(tokens.accept().clone())),
+        LeftSquare => list(tokens),
+        LeftCurly => record(tokens),
+        _ => {
+            let start = literal(tokens)?;
+
+            if !tokens.check_next(&[DotDot, DotDotEqual]) {
+                return Ok(Pattern::Literal(start));
+            }
+
+            let op = tokens.accept().clone();
+            let end = literal(tokens)?;
+
+            for bound in [&start, &end] {
+                if !is_number(&bound.token) {
+                    return Err(ParseError {
+                        token: bound.token.clone(),
+                        tag: InvalidRangeBound
+                    });
+                }
+            }
+
+            Ok(Pattern::Range {
+                start: start,
+                op: op,
+                end: end
+            })
+        }
+    }
+}
+
+fn literal(tokens: &mut TokenStream) -> Result<Literal, ParseError> {
+    let minus = if tokens.match_next(&[Minus]) {
+        Some(tokens.prev().clone())
+    } else {
+        None
+    };
+
+    let token = tokens.accept().clone();
+    let valid = match token.tag {
+        Integer(_) | Number(_) => true,
+        String(_) | True | False | Null => minus.is_none(),
+        _ => false
+    };
+
+    if !valid {
+        return Err(ParseError {
+            token: token,
+            tag: ExpectedPattern
+        });
+    }
+
+    // Only the negated literal may be that large
+    if token.tag == Integer(i64::MIN) && minus.is_none() {
+        return Err(ParseError {
+            token: token,
+            tag: IntegerOutOfRange
+        });
+    }
+
+    Ok(Literal {
+        minus: minus,
+        token: token
+    })
+}
+
+fn is_number(token: &Token) -> bool {
+    matches!(token.tag, Integer(_) | Number(_))
+}
+
+/// # Rule
+/// List pattern matches following grammary:
+/// ```ebnf
+/// list = '[' (pattern (',' pattern)* ','?)? ']';
+/// ```
+fn list(tokens: &mut TokenStream) -> Result<Pattern, ParseError> {
+    let lsquare = tokens.accept().clone();
+    let (items, rsquare) = separated(tokens, RightSquare, subpattern)?;
+
+    Ok(Pattern::List {
+        lsquare: lsquare,
+        items: items,
+        rsquare: rsquare
+    })
+}
+
+/// # Rule
+/// Record pattern matches following grammary:
+/// ```ebnf
+/// record = identifier? '{' (field (',' field)* ','?)? '}';
+/// field = (identifier | string) (':' pattern)?;
+/// ```
+fn record(tokens: &mut TokenStream) -> Result<Pattern, ParseError> {
+    let class = match tokens.current().tag {
+        Identifier(_) => Some(tokens.accept().clone()),
+        _ => None
+    };
+
+    let lcurly = tokens.require(&[LeftCurly])?.clone();
+    let (fields, rcurly) = separated(tokens, RightCurly, field)?;
+
+    Ok(Pattern::Record {
+        class: class,
+        lcurly: lcurly,
+        fields: fields,
+        rcurly: rcurly
+    })
+}
+
+fn field(tokens: &mut TokenStream) -> Result<FieldPattern, ParseError> {
+    let key = match tokens.current().tag {
+        Identifier(_) | String(_) => tokens.accept().clone(),
+        _ => return Err(ParseError {
+            token: tokens.current().clone(),
+            tag: ExpectedIdentifier
+        })
+    };
+
+    let pattern = if tokens.match_next(&[Colon]) {
+        Some(subpattern(tokens)?)
+    } else {
+        None
+    };
+
+    // Shorthand binds the value to the key, so it must be a name
+    if pattern.is_none() && !matches!(key.tag, Identifier(_)) {
+        return Err(ParseError {
+            token: tokens.current().clone(),
+            tag: ExpectedToken(Colon)
+        });
+    }
+
+    Ok(FieldPattern {
+        key: key,
+        pattern: pattern
+    })
+}
+
+/// Parse comma separated items up to the `end` token,
+/// which is returned along with them
+fn separated<T>(
+    tokens: &mut TokenStream,
+    end: TokenTag,
+    item: fn(&mut TokenStream) -> Result<T, ParseError>
+) -> Result<(Vec<T>, Token), ParseError> {
+    let mut items = vec![];
+
+    loop {
+        if tokens.current().tag == end {
+            break Ok((items, tokens.accept().clone()));
+        }
+
+        items.push(item(tokens)?);
+
+        if tokens.current().tag != end {
+            tokens.require(&[Comma])?;
+        }
+    }
+}
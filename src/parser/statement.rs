@@ -2,8 +2,9 @@ use std::rc::Rc;
 
 use crate::{
     lexer::token::{
-        TokenTag, Token
+        TokenTag
     },
+    errors::Span,
     parser::{
         ast::statement::Statement,
         token_stream::TokenStream,
@@ -15,6 +16,8 @@ use crate::{
 };
 use super::ast::statement::{
     Func,
+    Params,
+    Param,
     Group,
     ExprStatment,
     Assignment,
@@ -22,9 +25,36 @@ use super::ast::statement::{
     Print,
     Cond,
     Loop,
-    Return
+    Repeat,
+    RepeatUntil,
+    Return,
+    Break,
+    Continue,
+    Class,
+    Match,
+    Arm,
+    Throw,
+    Try,
+    Catch,
+    Import
 };
-use crate::parser::expression::expression;
+use crate::parser::{
+    expression::expression,
+    pattern::pattern,
+    ast::expression::{
+        Expression,
+        ExpressionNode,
+        PrimaryNode
+    }
+};
+
+const ASSIGNMENT_OPERATORS: &[TokenTag] = &[
+    TokenTag::ArrowLeft,
+    TokenTag::PlusEqual,
+    TokenTag::MinusEqual,
+    TokenTag::StarEqual,
+    TokenTag::SlashEqual,
+];
 
 /// This function implements statement parsing
 /// # Arguments
@@ -33,30 +63,45 @@ use crate::parser::expression::expression;
 /// All available statements is defeined here
 ///
 pub fn statement(tokens: &mut TokenStream) -> Result<Statement, ParseError> {
-    let token = tokens.accept();
+    let tag = tokens.accept().tag.clone();
 
-    let stmt = match token.tag {
+    let stmt = match tag {
         TokenTag::Print     => Statement::Print(print(tokens)?),
         TokenTag::If        => Statement::Cond(cond(tokens)?),
+        TokenTag::Match     => Statement::Match(r#match(tokens)?),
         TokenTag::Loop      => Statement::Loop(r#loop(tokens)?),
-        TokenTag::LeftCurly => Statement::Group(group(tokens)?),
+        TokenTag::Repeat    => repeat(tokens)?,
+        TokenTag::LeftCurly => {
+            tokens.discard(); // group requires opening curly itself
+            Statement::Group(group(tokens)?)
+        },
         TokenTag::Let       => Statement::Let(var_definition(tokens)?),
-        TokenTag::Func      => Statement::Func(func_definition(tokens)?),
+        TokenTag::Func if matches!(
+            tokens.current().tag,
+            TokenTag::Identifier(_)
+        ) => Statement::Func(func_definition(tokens)?),
+        TokenTag::Class     => Statement::Class(class(tokens)?),
+        TokenTag::Import    => Statement::Import(import(tokens)?),
         TokenTag::Return    => Statement::Retrun(r#return(tokens)?),
-        TokenTag::Identifier(_) => {
-            if tokens.check_next(&[TokenTag::ArrowLeft]) {
-                Statement::Assign(assignment(tokens)?)
-            } else {
-                Statement::Expr(expr_stmt(tokens)?)
-            }
-        },
+        TokenTag::Throw     => Statement::Throw(throw(tokens)?),
+        TokenTag::Try       => Statement::Try(r#try(tokens)?),
+        TokenTag::Break     => Statement::Break(Break {
+            keyword: tokens.prev().clone()
+        }),
+        TokenTag::Continue  => Statement::Continue(Continue {
+            keyword: tokens.prev().clone()
+        }),
         _ => {
             tokens.discard(); // discard the token we accepted
-            Statement::Expr(expr_stmt(tokens)?)
+            expr_stmt(tokens)?
         }
     };
 
-    if tokens.prev().tag != TokenTag::RightCurly {
+    // Semicolon is optional after the closing curly brace,
+    // and before it, so the last expression of the block can go without it
+    if tokens.prev().tag == TokenTag::RightCurly {
+        tokens.match_next(&[TokenTag::Semicolon]);
+    } else if tokens.current().tag != TokenTag::RightCurly {
         tokens.require(&[TokenTag::Semicolon])?;
     }
 
@@ -66,7 +111,7 @@ pub fn statement(tokens: &mut TokenStream) -> Result<Statement, ParseError> {
 /// # Rule
 /// Function definition matches following grammary:
 /// ```ebnf
-/// func = 'func' identifier (params) -> statement;
+/// func = 'func' identifier params '->' func_body;
 /// ```
 fn func_definition(
     tokens: &mut TokenStream
@@ -84,36 +129,145 @@ fn func_definition(
 
     tokens.require(&[TokenTag::ArrowRight])?;
 
-    let body = group(tokens)?;
+    let body = func_body(tokens)?;
 
     Ok(Func {
         keyword: keyword,
         name: identifier,
-        params: params,
+        params: Rc::new(params),
         body: Rc::new(body),
     })
 }
 
+/// # Rule
+/// Class declaration matches following grammary:
+/// ```ebnf
+/// class = 'class' identifier '{' (define ';' | func ';'?)* '}';
+/// ```
+fn class(tokens: &mut TokenStream) -> Result<Class, ParseError> {
+    let keyword = tokens.prev().clone();
+    let name = match tokens.current().tag {
+        TokenTag::Identifier(_) => tokens.accept().clone(),
+        _ => return Err(ParseError {
+            token: tokens.current().clone(),
+            tag: ExpectedIdentifier
+        })
+    };
+
+    let mut fields = vec![];
+    let mut methods = vec![];
+
+    tokens.require(&[TokenTag::LeftCurly])?;
+
+    loop {
+        match tokens.accept().tag {
+            TokenTag::Let => {
+                fields.push(var_definition(tokens)?);
+                tokens.require(&[TokenTag::Semicolon])?;
+            },
+            TokenTag::Func => {
+                methods.push(func_definition(tokens)?);
+
+                if tokens.prev().tag == TokenTag::RightCurly {
+                    tokens.match_next(&[TokenTag::Semicolon]);
+                } else {
+                    tokens.require(&[TokenTag::Semicolon])?;
+                }
+            },
+            TokenTag::RightCurly => break,
+            _ => return Err(ParseError {
+                token: tokens.prev().clone(),
+                tag: UnexpectedToken
+            })
+        }
+    }
+
+    Ok(Class {
+        keyword: keyword,
+        name: name,
+        fields: Rc::new(fields),
+        methods: methods
+    })
+}
+
+/// # Rule
+/// Function body is either a group, or a single expression,
+/// which is returned from the function:
+/// ```ebnf
+/// func_body = group | expression;
+/// ```
+pub fn func_body(tokens: &mut TokenStream) -> Result<Group, ParseError> {
+    if tokens.check_next(&[TokenTag::LeftCurly]) {
+        return group(tokens);
+    }
+
+    let arrow = tokens.prev().clone();
+    let expr = expression(tokens)?;
+    let end = Span::from(expr.as_ref().clone()).end;
+
+    Ok(Group {
+        lcurly: arrow.clone(),
+        stmts: vec![Statement::Retrun(Return {
+            keyword: arrow,
+            expr: expr
+        })],
+        rcurly: end
+    })
+}
+
 /// # Rule
 /// Function params matches following grammary:
 /// ```ebnf
-/// params = param (',' param)*;
-/// param = identifier;
+/// params = '(' (param (',' param)* (',' rest)? | rest)? ')';
+/// param = identifier ('<-' expression)?;
+/// rest = '...' identifier;
 /// ```
-fn parse_params(tokens: &mut TokenStream) -> Result<Vec<Token>, ParseError> {
-    let mut params = vec![];
+/// Parameters without default value can't follow the ones with it.
+pub fn parse_params(tokens: &mut TokenStream) -> Result<Params, ParseError> {
+    let mut params = Params::default();
 
     tokens.require(&[TokenTag::LeftParen])?;
 
     loop {
         match tokens.current().tag {
             TokenTag::Identifier(_) => {
-                params.push(tokens.accept().clone());
+                let name = tokens.accept().clone();
+                let default = match tokens.match_next(&[TokenTag::ArrowLeft]) {
+                    true => Some(expression(tokens)?),
+                    false => None
+                };
+
+                if default.is_none() && params.required() < params.params.len() {
+                    return Err(ParseError {
+                        token: name,
+                        tag: RequiredAfterDefault
+                    });
+                }
+
+                params.params.push(Param {
+                    name: name,
+                    default: default
+                });
 
                 if tokens.current().tag != TokenTag::RightParen {
                     tokens.require(&[TokenTag::Comma])?;
                 }
             },
+            // Rest parameter is the last one
+            TokenTag::DotDotDot => {
+                tokens.accept();
+
+                params.rest = match tokens.current().tag {
+                    TokenTag::Identifier(_) => Some(tokens.accept().clone()),
+                    _ => return Err(ParseError {
+                        token: tokens.current().clone(),
+                        tag: ExpectedIdentifier
+                    })
+                };
+
+                tokens.require(&[TokenTag::RightParen])?;
+                break Ok(params);
+            },
             TokenTag::RightParen => {
                 tokens.accept();
                 break Ok(params);
@@ -126,6 +280,27 @@ fn parse_params(tokens: &mut TokenStream) -> Result<Vec<Token>, ParseError> {
     }
 }
 
+/// # Rule
+/// Import statement matches following grammary:
+/// ```ebnf
+/// import = 'import' (identifier | string) ';';
+/// ```
+fn import(tokens: &mut TokenStream) -> Result<Import, ParseError> {
+    let keyword = tokens.prev().clone();
+    let module = match tokens.current().tag {
+        TokenTag::Identifier(_) | TokenTag::String(_) => tokens.accept().clone(),
+        _ => return Err(ParseError {
+            token: tokens.current().clone(),
+            tag: ExpectedIdentifier
+        })
+    };
+
+    Ok(Import {
+        keyword: keyword,
+        module: module
+    })
+}
+
 /// # Rule
 /// Return statement matches following grammary:
 /// ```ebnf
@@ -138,6 +313,66 @@ fn r#return(tokens: &mut TokenStream) -> Result<Return, ParseError> {
     })
 }
 
+/// # Rule
+/// Throw statement matches following grammary:
+/// ```ebnf
+/// throw = 'throw' expression ';';
+/// ```
+fn throw(tokens: &mut TokenStream) -> Result<Throw, ParseError> {
+    Ok(Throw {
+        keyword: tokens.prev().clone(),
+        expr: expression(tokens)?,
+    })
+}
+
+/// # Rule
+/// Try statement matches following grammary:
+/// ```ebnf
+/// try = 'try' group ('catch' identifier group)? ('finally' group)?;
+/// ```
+/// At least one of `catch` and `finally` is required.
+fn r#try(tokens: &mut TokenStream) -> Result<Try, ParseError> {
+    let keyword = tokens.prev().clone();
+    let body = Box::new(group(tokens)?);
+    let mut catch = None;
+    let mut finally = None;
+
+    if tokens.match_next(&[TokenTag::Catch]) {
+        let keyword = tokens.prev().clone();
+        let name = match tokens.current().tag {
+            TokenTag::Identifier(_) => tokens.accept().clone(),
+            _ => return Err(ParseError {
+                token: tokens.current().clone(),
+                tag: ExpectedIdentifier
+            })
+        };
+
+        catch = Some(Catch {
+            keyword: keyword,
+            name: name,
+            body: Box::new(group(tokens)?)
+        });
+    }
+
+    if tokens.match_next(&[TokenTag::Finally]) {
+        finally = Some(Box::new(group(tokens)?));
+    }
+
+    if catch.is_none() && finally.is_none() {
+        return Err(ParseError {
+            token: tokens.current().clone(),
+            tag: ExpectedToken(TokenTag::Catch)
+        });
+    }
+
+    Ok(Try {
+        keyword: keyword,
+        body: body,
+        catch: catch,
+        finally: finally
+    })
+}
+
 /// # Rule
 /// Loop statement matches following grammary:
 /// ```ebnf
@@ -151,12 +386,38 @@ fn r#loop(tokens: &mut TokenStream) -> Result<Loop, ParseError> {
     })
 }
 
+/// # Rule
+/// Repeat statement matches following grammary:
+/// ```ebnf
+/// repeat = 'repeat' (expression group | group 'until' expression);
+/// ```
+fn repeat(tokens: &mut TokenStream) -> Result<Statement, ParseError> {
+    let keyword = tokens.prev().clone();
+
+    if tokens.check_next(&[TokenTag::LeftCurly]) {
+        let body = Box::new(group(tokens)?);
+        tokens.require(&[TokenTag::Until])?;
+
+        return Ok(Statement::RepeatUntil(RepeatUntil {
+            keyword: keyword,
+            body: body,
+            condition: expression(tokens)?,
+        }));
+    }
+
+    Ok(Statement::Repeat(Repeat {
+        keyword: keyword,
+        count: expression(tokens)?,
+        body: Box::new(group(tokens)?),
+    }))
+}
+
 /// # Rule
 /// Conditional statement matches following grammary:
 /// ```ebnf
 /// cond = 'if' expression group ('else' group)?;
 /// ```
-fn cond(tokens: &mut TokenStream) -> Result<Cond, ParseError> {
+pub fn cond(tokens: &mut TokenStream) -> Result<Cond, ParseError> {
     let keyword = tokens.prev().clone();
     let condition = expression(tokens);
     let if_block = group(tokens);
@@ -174,17 +435,75 @@ fn cond(tokens: &mut TokenStream) -> Result<Cond, ParseError> {
     })
 }
 
+/// # Rule
+/// Match statement matches following grammary:
+/// ```ebnf
+/// match = 'match' expression '{' (arm (',' arm)* ','?)? '}';
+/// arm = pattern ('if' expression)? '->' (group | expression);
+/// ```
+/// Comma after the arm with the group body can be omitted.
+pub fn r#match(tokens: &mut TokenStream) -> Result<Match, ParseError> {
+    let keyword = tokens.prev().clone();
+    let scrutinee = expression(tokens)?;
+    let mut arms = vec![];
+
+    tokens.require(&[TokenTag::LeftCurly])?;
+
+    loop {
+        if tokens.current().tag == TokenTag::RightCurly {
+            break;
+        }
+
+        arms.push(arm(tokens)?);
+
+        if tokens.prev().tag == TokenTag::RightCurly {
+            tokens.match_next(&[TokenTag::Comma]);
+        } else if tokens.current().tag != TokenTag::RightCurly {
+            tokens.require(&[TokenTag::Comma])?;
+        }
+    }
+
+    Ok(Match {
+        keyword: keyword,
+        scrutinee: scrutinee,
+        arms: arms,
+        rcurly: tokens.accept().clone()
+    })
+}
+
+fn arm(tokens: &mut TokenStream) -> Result<Arm, ParseError> {
+    let pattern = pattern(tokens)?;
+    let mut guard = None;
+
+    if tokens.match_next(&[TokenTag::If]) {
+        guard = Some(expression(tokens)?);
+    }
+
+    tokens.require(&[TokenTag::ArrowRight])?;
+
+    // Body in curly braces is always a group, even if it looks like a map
+    let body = if tokens.check_next(&[TokenTag::LeftCurly]) {
+        Expression::create(ExpressionNode::Block(Rc::new(group(tokens)?)))
+    } else {
+        expression(tokens)?
+    };
+
+    Ok(Arm {
+        pattern: pattern,
+        guard: guard,
+        body: body
+    })
+}
+
 /// # Rule
 /// Group statement matches following grammary:
 /// ```ebnf
 /// group = '{' statement* '}';
 /// ```
-fn group(tokens: &mut TokenStream) -> Result<Group, ParseError> {
-    let lcurly = tokens.prev().clone();
+pub fn group(tokens: &mut TokenStream) -> Result<Group, ParseError> {
+    let lcurly = tokens.require(&[TokenTag::LeftCurly])?.clone();
     let mut group = vec![];
 
-    tokens.require(&[TokenTag::LeftCurly])?;
-
     while tokens.current().tag != TokenTag::RightCurly {
         group.push(statement(tokens)?);
     }
@@ -240,20 +559,30 @@ fn var_definition(
 /// # Rule
 /// Variable assignment matches following grammary:
 /// ```ebnf
-/// assign = identifier ('<-') expression;
+/// assign = target ('<-' | '+=' | '-=' | '*=' | '/=') expression;
+/// target = identifier | call '[' expression ']' | call '.' identifier;
 /// ```
 fn assignment(
     tokens: &mut TokenStream,
+    target: Box<Expression>
 ) -> Result<Assignment, ParseError> {
-    let identifier = tokens.prev().clone();
+    let operator = tokens.prev().clone();
 
-    tokens.require(&[
-        TokenTag::ArrowLeft
-    ])?;
+    match target.get_node() {
+        ExpressionNode::Primary(
+            PrimaryNode::Identifier(_)
+            | PrimaryNode::Index { .. }
+            | PrimaryNode::Property { .. }
+        ) => (),
+        _ => return Err(ParseError {
+            token: operator,
+            tag: InvalidAssignmentTarget
+        })
+    };
 
     Ok(Assignment {
-        name: identifier,
-        operator: tokens.prev().clone(),
+        target: target,
+        operator: operator,
         expr: expression(tokens)?
     })
 }
@@ -261,12 +590,18 @@ fn assignment(
 /// # Rule
 /// Expression statement matches following grammary:
 /// ```ebnf
-/// expr_stmt = expression;
+/// expr_stmt = expression | assign;
 /// ```
 fn expr_stmt(
     tokens: &mut TokenStream,
-) -> Result<ExprStatment, ParseError> {
-    Ok(ExprStatment {
-        expr: expression(tokens)?
-    })
+) -> Result<Statement, ParseError> {
+    let expr = expression(tokens)?;
+
+    if tokens.match_next(ASSIGNMENT_OPERATORS) {
+        return Ok(Statement::Assign(assignment(tokens, expr)?));
+    }
+
+    Ok(Statement::Expr(ExprStatment {
+        expr: expr
+    }))
 }
@@ -0,0 +1,4 @@
+mod repl;
+mod tests;
+
+pub use repl::Repl;
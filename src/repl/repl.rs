@@ -0,0 +1,163 @@
+use rustyline::{
+    DefaultEditor,
+    error::ReadlineError
+};
+use crate::{
+    engine::Engine,
+    interpreter::Value,
+    lexer::{
+        Lexer,
+        token::TokenTag
+    }
+};
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+
+/// Dialog mode.
+/// All the inputs are executed by the same engine, so definitions
+/// made in one input are visible in the following ones.
+pub struct Repl {
+    engine: Engine,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self {
+            engine: Engine::new()
+        }
+    }
+
+    pub fn run(&mut self) {
+        let mut editor = match DefaultEditor::new() {
+            Ok(editor) => editor,
+            Err(err) => {
+                eprintln!("Fatal error: failed to start dialog mode ({err})");
+                return;
+            }
+        };
+
+        let mut input = Input::default();
+
+        loop {
+            let prompt = if input.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+
+            match editor.readline(prompt) {
+                Ok(line) => {
+                    if let Some(src) = input.push(&line) {
+                        let _ = editor.add_history_entry(src.trim_end());
+                        self.eval(&src);
+                    }
+                },
+                // Ctrl-C drops current input
+                Err(ReadlineError::Interrupted) => input.clear(),
+                // Ctrl-D ends the session
+                Err(ReadlineError::Eof) => break,
+                Err(err) => {
+                    eprintln!("Fatal error: {err}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Execute the input, echoing the values of its expression statements.
+    /// Errors are reported, but never end the session.
+    fn eval(&mut self, src: &str) {
+        let result = self.engine.eval_each(src, |value| {
+            if *value == Value::Null {
+                return;
+            }
+
+            if let Ok(str) = value.to_string() {
+                println!("{str}");
+            }
+        });
+
+        if let Err(error) = result {
+            error.print();
+        }
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lines of the input being entered
+#[derive(Default)]
+pub(super) struct Input {
+    buffer: String
+}
+
+impl Input {
+    /// Add the line to the input, returning the whole input
+    /// once it's ready to be evaluated
+    pub(super) fn push(&mut self, line: &str) -> Option<String> {
+        // Empty line forces incomplete input to be evaluated,
+        // so the user can see what is wrong with it
+        let forced = !self.buffer.is_empty() && line.trim().is_empty();
+
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+
+        if self.buffer.trim().is_empty() {
+            self.buffer.clear();
+            return None;
+        }
+
+        if !forced && !is_complete(&self.buffer) {
+            return None;
+        }
+
+        Some(std::mem::take(&mut self.buffer))
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.buffer.clear()
+    }
+}
+
+/// Input is incomplete if it has unclosed brackets of any kind,
+/// or it doesn't end with `;` or `}`.
+fn is_complete(src: &str) -> bool {
+    let tokens = match Lexer::from_string(src.into()).lex() {
+        Ok(tokens) => tokens,
+        // Lexical errors will be reported on evaluation anyway
+        Err(_) => return true
+    };
+
+    let mut depth = 0;
+
+    for token in &tokens {
+        match token.tag {
+            TokenTag::LeftCurly
+            | TokenTag::LeftParen
+            | TokenTag::LeftSquare => depth += 1,
+            TokenTag::RightCurly
+            | TokenTag::RightParen
+            | TokenTag::RightSquare => depth -= 1,
+            _ => ()
+        }
+    }
+
+    let last = tokens
+        .iter()
+        .rev()
+        .find(|token| token.tag != TokenTag::EndOfFile);
+
+    depth <= 0 && matches!(
+        last.map(|token| &token.tag),
+        Some(TokenTag::Semicolon | TokenTag::RightCurly)
+    )
+}
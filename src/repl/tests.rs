@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use crate::repl::repl::Input;
+
+    #[test]
+    fn complete_statement_is_evaluated_at_once() {
+        let mut input = Input::default();
+
+        assert_eq!(input.push("print 1;"), Some("print 1;\n".into()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn open_braces_continue_input() {
+        let mut input = Input::default();
+
+        assert_eq!(input.push("func f() -> {"), None);
+        assert_eq!(input.push("    let xs <- [1,"), None);
+        assert_eq!(input.push("        2];"), None);
+        assert_eq!(
+            input.push("}"),
+            Some("func f() -> {\n    let xs <- [1,\n        2];\n}\n".into())
+        );
+    }
+
+    #[test]
+    fn missing_semicolon_continues_input() {
+        let mut input = Input::default();
+
+        assert_eq!(input.push("1 + 2"), None);
+        assert!(!input.is_empty());
+        assert_eq!(input.push(";"), Some("1 + 2\n;\n".into()));
+    }
+
+    #[test]
+    fn empty_line_flushes_incomplete_input() {
+        let mut input = Input::default();
+
+        assert_eq!(input.push("let a <- ("), None);
+        assert_eq!(input.push(""), Some("let a <- (\n\n".into()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn empty_lines_are_skipped() {
+        let mut input = Input::default();
+
+        assert_eq!(input.push("  "), None);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn lexical_errors_are_evaluated_at_once() {
+        let mut input = Input::default();
+
+        assert!(input.push("\"unterminated").is_some());
+    }
+}